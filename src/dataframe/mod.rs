@@ -3,30 +3,35 @@ use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
 use rayon::prelude::*;
 use std::fs;
+use std::io::{BufRead, BufReader};
 use crate::series::*;
+use crate::json;
 use num_traits::Zero;
 use std::ops::Index;
 use std::fmt::{Display, Formatter, Result};
 use glob::glob;
 use std::collections::HashMap;
+use std::borrow::Cow;
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
 use prettytable::{Table, Row};
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
 
-const LOWER_PAR_BOUND: usize = 8192;
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[pyclass]
 pub struct DataFrame {
     header_row: Vec<String>, 
     cols: Vec<Series>,
     rows: Vec<Series>,
-    pub size: usize
+    pub size: usize,
+    // Row labels set by `set_index`, one per row
+    index: Option<Series>
 }
 
 macro_rules! parse_axis {
     ($self:ident, $method:ident, $axis: expr) => {
         if $axis == 0 { 
-            if $self.cols.len() < LOWER_PAR_BOUND {
+            if $self.cols.len() < Series::par_threshold() {
                 DataFrame::new($self.cols.iter().map(|s| s.$method()).collect(), Some($self.header_row.clone())) 
             }
             else {
@@ -34,7 +39,7 @@ macro_rules! parse_axis {
             }
         }
         else { 
-            if $self.rows.len() < LOWER_PAR_BOUND {
+            if $self.rows.len() < Series::par_threshold() {
                 DataFrame::new($self.rows.iter().map(|s| s.$method()).collect(), None) 
             }
             else {
@@ -94,7 +99,8 @@ impl DataFrame {
             header_row : header,
             cols : data, 
             rows,
-            size 
+            size,
+            index: None
         }
     }
     
@@ -174,11 +180,80 @@ impl DataFrame {
         }
     }
 
+    /// Moves a column out of the data to label the rows instead, so they can be looked up with
+    /// `loc_row`. Numeric labels are matched by their formatted value, e.g. 3.0 as `"3"`
+    ///
+    /// Operations that keep the rows (like `select`, `drop_col`, `sort_values` and `filter`) keep
+    /// the labels too, while ones that compute new values (like `plus` or `sum`) drop them
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and label the rows by UserID
+    /// | UserID |  Age  | Height |
+    /// |   7    |   42  |  183   |
+    /// |   8    |   21  |  160   |
+    /// |   9    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![7.0, 8.0, 9.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header)).set_index("UserID");
+    /// assert_eq!(df.loc_col("UserID"), None);
+    /// assert_eq!(df.loc_row("8").unwrap(), Series::new(vec![21.0, 160.0]));
+    /// ```
+    pub fn set_index(&self, col: &str) -> DataFrame {
+        let idx = self.col_idx(col);
+        self.drop_col_idx(idx).with_index(Some(self.cols[idx].clone()))
+    }
+
+    /// Extract a row from the DataFrame by its label (see `set_index`). Without any labels, the
+    /// label is read as the row's position instead
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and access a row, with and without labelling the rows by
+    /// UserID
+    /// | UserID |  Age  | Height |
+    /// |   7    |   42  |  183   |
+    /// |   8    |   21  |  160   |
+    /// |   9    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![7.0, 8.0, 9.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let labelled: DataFrame = df.set_index("UserID");
+    /// assert_eq!(labelled.loc_row("9").unwrap(), Series::new(vec![8.0, 132.0]));
+    /// assert_eq!(labelled.loc_row("10"), None);
+    ///
+    /// // Without labels, rows are looked up by position
+    /// assert_eq!(df.loc_row("2").unwrap(), Series::new(vec![9.0, 8.0, 132.0]));
+    /// assert_eq!(df.loc_row("9"), None);
+    /// ```
+    pub fn loc_row(&self, label: &str) -> Option<Series> {
+        match &self.index {
+            Some(labels) => (0..labels.size())
+                .find(|&i| labels.field(i) == label)
+                .map(|i| self.irow(i)),
+            None => label.parse::<usize>().ok()
+                .filter(|&i| i < self.rows.len())
+                .map(|i| self.irow(i))
+        }
+    }
+
     /// Returns the length/size of DataFrame
     ///
     /// # Examples
     ///
-    /// Create a new DataFrame of the form and access the find the size
+    /// Create a new DataFrame of the form and find its size, i.e. the number of values in it
     /// | UserID |  Age  | Height |
     /// |   0    |   42  |  183   |
     /// |   1    |   21  |  160   |
@@ -192,10 +267,116 @@ impl DataFrame {
     ///     Series::new(vec![183.0, 160.0, 132.0])
     /// ];
     /// let df: DataFrame = DataFrame::new(data, Some(header));
-    /// assert_eq!(df.size(), 3usize);
+    /// assert_eq!(df.size(), 9usize);
+    /// assert_eq!(df.size(), df.size);
     /// ```
     pub fn size(&self) -> usize {
-        self.cols.len() as usize
+        self.size
+    }
+
+    /// Returns the number of rows in the DataFrame
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and count its rows
+    /// | UserID |  Age  |
+    /// |   0    |   42  |
+    /// |   1    |   21  |
+    /// |   2    |   8   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// assert_eq!(df.nrows(), 3usize);
+    /// ```
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the number of columns in the DataFrame
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and count its columns
+    /// | UserID |  Age  |
+    /// |   0    |   42  |
+    /// |   1    |   21  |
+    /// |   2    |   8   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// assert_eq!(df.ncols(), 2usize);
+    /// ```
+    pub fn ncols(&self) -> usize {
+        self.cols.len()
+    }
+
+    /// Estimates the heap bytes held by the DataFrame: 8 bytes per value plus the text of the
+    /// header and any string columns or row labels
+    ///
+    /// Every value is stored twice, once in its column and once in its row, so this comes out
+    /// at roughly double what the data alone would take
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and estimate its memory usage
+    /// | UserID |  Age  |
+    /// |   0    |   42  |
+    /// |   1    |   21  |
+    /// |   2    |   8   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// // 6 values stored twice, plus "UserID" and "Age"
+    /// assert_eq!(df.memory_usage(), 2 * 6 * 8 + 9);
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        let header: usize = self.header_row.iter().map(|h| h.len()).sum();
+        let cols: usize = self.cols.iter().map(|c| c.heap_bytes()).sum();
+        let rows: usize = self.rows.iter().map(|r| r.heap_bytes()).sum();
+        let index = self.index.as_ref().map_or(0, |labels| labels.heap_bytes());
+        header + cols + rows + index
+    }
+
+    /// Returns the dimensions of the DataFrame as `(rows, columns)`. Their product is `size()`
+    ///
+    /// In Python this is a property, i.e. `df.shape`
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and find its shape
+    /// | UserID |  Age  |
+    /// |   0    |   42  |
+    /// |   1    |   21  |
+    /// |   2    |   8   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// assert_eq!(df.shape(), (3, 2));
+    /// assert_eq!(df.size, 3 * 2);
+    /// ```
+    #[getter]
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows.len(), self.cols.len())
     }
 
     /// Drops any rows/columns that contain missing values
@@ -248,6 +429,34 @@ impl DataFrame {
         self.dropna(axis) // clickbaited
     }
 
+    /// Replaces all missing values in the DataFrame with a value, keeping rows aligned
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and fill in the missing heights
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  NaN   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  NaN   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![f64::NAN, 160.0, f64::NAN])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// assert_eq!(df.fillna(0.0).loc_col("Height").unwrap(), Series::new(vec![0.0, 160.0, 0.0]));
+    /// ```
+    pub fn fillna(&self, value: f64) -> DataFrame {
+        let header = Some(self.header_row.clone());
+        let filled = (&self.cols).into_par_iter()
+            .map(|x| x.fillna(value))
+            .collect();
+        DataFrame::new(filled, header)
+    }
+
     /// Sums each Series in the DataFrame
     ///
     /// # Examples
@@ -393,7 +602,9 @@ impl DataFrame {
         parse_axis!(self, mode, axis)
     }
 
-    /// Calculates the variance for each Series in the DataFrame
+    /// Calculates the variance for each Series in the DataFrame. Along axis 0 the columns with a
+    /// missing value are skipped, and along axis 1 each column's variance is taken over only the
+    /// rows without a missing value
     ///
     /// # Examples
     ///
@@ -413,17 +624,17 @@ impl DataFrame {
     /// let df: DataFrame = DataFrame::new(data, Some(header));
     /// 
     /// // Variance across columns
-    /// println!("{}", df.var(0))
+    /// println!("{}", df.var(0));
     /// 
-    /// // Variance across rows
-    /// println!("{}", df.var(1))
+    /// // Variance of each column over the complete rows
+    /// println!("{}", df.var(1));
     /// ```
     pub fn var(&self, axis: usize) -> DataFrame {
-        let valid = self.dropna(axis);
-        parse_axis!(valid, var, axis)
+        self.agg_complete(Series::var, axis)
     }
 
-    /// Calculates the standard deviation for each Series in the DataFrame
+    /// Calculates the standard deviation for each Series in the DataFrame, skipping missing
+    /// values the same way `var` does
     ///
     /// # Examples
     ///
@@ -443,14 +654,13 @@ impl DataFrame {
     /// let df: DataFrame = DataFrame::new(data, Some(header));
     /// 
     /// // Standard deviation across columns
-    /// println!("{}", df.std(0))
+    /// println!("{}", df.std(0));
     /// 
-    /// // Standard deviation across rows
-    /// println!("{}", df.std(1))
+    /// // Standard deviation of each column over the complete rows
+    /// println!("{}", df.std(1));
     /// ```
     pub fn std(&self, axis: usize) -> DataFrame {
-        let valid = self.dropna(axis);
-        parse_axis!(valid, std, axis)
+        self.agg_complete(Series::std, axis)
     }
 
     /// Calculates the minimum for each Series in the DataFrame
@@ -510,9 +720,10 @@ impl DataFrame {
     pub fn max(&self, axis: usize) -> DataFrame {
         parse_axis!(self, max, axis)
     }
-   /* 
-
-    /// Applies a function to all values inside the DataFrame
+    /// Applies a Python function to every value inside the DataFrame, keeping the header
+    ///
+    /// Like `Series::apply`, the function is called once per value while holding the GIL, so this
+    /// runs sequentially and is much slower than the built-in element wise methods
     ///
     /// # Examples
     ///
@@ -521,32 +732,84 @@ impl DataFrame {
     /// |   0    |   42  |  183   |
     /// |   1    |   21  |  160   |
     /// |   2    |   8   |  132   |
+    /// ```python
+    /// from rusty_pandas import DataFrame, Series
+    ///
+    /// df = DataFrame([
+    ///     Series([0.0, 1.0, 2.0]),
+    ///     Series([42.0, 21.0, 8.0]),
+    ///     Series([183.0, 160.0, 132.0])
+    /// ], ["UserID", "Age", "Height"])
+    /// applied = df.apply(lambda x: x / 10.0)
+    /// assert applied.loc_col("Age").to_vec() == [4.2, 2.1, 0.8]
+    /// ```
+    pub fn apply(&self, py: Python, f: PyObject) -> PyResult<DataFrame> {
+        let applied = self.cols.iter()
+            .map(|col| col.apply(py, f.clone_ref(py)))
+            .collect::<PyResult<Vec<Series>>>()?;
+        Ok(DataFrame::new(applied, Some(self.header_row.clone())))
+    }
+
+    /// Swaps the rows and columns of the DataFrame
+    ///
+    /// Since every value has to be a number the header can't become a column of labels, so it is
+    /// dropped and the new columns are named by position like a DataFrame created without one
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and transpose it
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
     /// ```
     ///
     /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
     /// let data: Vec<Series> = vec![
-    ///     Series::new(vec![0.0, 1.0, 2.0]),
-    ///     Series::new(vec![42.0, 21.0, 8.0]),
-    ///     Series::new(vec![183.0, 160.0, 132.0])
+    ///     Series::new(vec![0.0, 1.0]),
+    ///     Series::new(vec![42.0, 21.0]),
+    ///     Series::new(vec![183.0, 160.0])
     /// ];
     /// let df: DataFrame = DataFrame::new(data, Some(header));
-    /// let f = |x: f64| -> f64 { x / 10.0 };
-    /// df.apply(f);
+    /// let transposed: DataFrame = df.transpose();
+    /// assert_eq!(transposed.shape(), (3, 2));
+    /// assert_eq!(transposed.loc_col("1").unwrap(), Series::new(vec![1.0, 21.0, 160.0]));
     /// ```
-    pub fn apply(&self, f: fn(f64) -> f64) -> DataFrame {
-        let header = Some(self.header_row.clone());
-        let applied = (&self.cols).into_par_iter()
-            .map(|x| Series::new(x.to_vec().into_par_iter().map(|x| f(x)).collect()))
-            .collect();
-        DataFrame::new(applied, header)
+    pub fn transpose(&self) -> DataFrame {
+        DataFrame::new(self.rows.clone(), None)
     }
-    */
 
     /// Creates a deepcopy of a DataFrame
     pub fn copy(&self) -> DataFrame {
         let data_copy = self.cols.clone().into_par_iter().map(|col| col.clone()).collect();
         let header_copy = self.header_row.clone();
-        DataFrame::new(data_copy, Some(header_copy))
+        DataFrame::new(data_copy, Some(header_copy)).with_index(self.index.clone())
+    }
+
+    /// Checks whether two DataFrames have the same header and the same values to within `tol`,
+    /// with NaNs counting as equal (see `Series::approx_eq`)
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and compare it to a computed one
+    /// | UserID |  Age  |
+    /// |   0    |   42  |
+    /// |   1    |   21  |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0]),
+    ///     Series::new(vec![42.0, 21.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let round_trip: DataFrame = df.div(3.0).mult(3.0);
+    /// assert!(df.equals(&round_trip, 1e-9));
+    /// assert!(!df.equals(&df.plus(0.1), 1e-9));
+    /// ```
+    pub fn equals(&self, other: &DataFrame, tol: f64) -> bool {
+        self.header_row == other.header_row &&
+        self.cols.len() == other.cols.len() &&
+        self.cols.iter().zip(&other.cols).all(|(a, b)| a.approx_eq(b, tol))
     }
 
     /// Writes the contents of the DataFrame to a CSV file
@@ -571,8 +834,72 @@ impl DataFrame {
     /// df.to_csv(path);
     /// ```
     pub fn to_csv(&self, filename: &str) {
-        let header: String = self.header_row.join(",") + "\n";
-        let out: Vec<String> = (&self.rows).into_par_iter().map(|r| r.join(",")).collect();
+        self.to_csv_opts(filename, "NaN", None, false, None)
+    }
+
+    /// Writes the contents of the DataFrame to a CSV file, writing missing values as `na_rep` and
+    /// numbers with `precision` decimal places if given (string columns and row labels are written
+    /// as they are)
+    ///
+    /// Real pandas writes missing values as empty cells, so pass `""` as `na_rep` to match it.
+    /// With `index` a leading column holds the row labels from `set_index`, or the row positions
+    /// if there are none, headed by `index_label` (or left blank like pandas does)
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and write it to a CSV file
+    /// | UserID |  Age  | Height |
+    /// |   0    |  NaN  | 183.26 |
+    /// |   1    |   21  |  160   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0]),
+    ///     Series::new(vec![f64::NAN, 21.0]),
+    ///     Series::new(vec![183.26, 160.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let path: &str = "/tmp/wtfbbq.csv";
+    /// df.to_csv_opts(path, "", Some(1), false, None);
+    /// assert_eq!(std::fs::read_to_string(path).unwrap(), "UserID,Age,Height\n0.0,,183.3\n1.0,21.0,160.0");
+    ///
+    /// df.to_csv_opts(path, "NaN", None, true, Some("row".to_string()));
+    /// assert_eq!(std::fs::read_to_string(path).unwrap(), "row,UserID,Age,Height\n0,0,NaN,183.26\n1,1,21,160");
+    ///
+    /// // The row labels aren't rounded
+    /// df.to_csv_opts(path, "", Some(1), true, None);
+    /// assert_eq!(std::fs::read_to_string(path).unwrap(), ",UserID,Age,Height\n0,0.0,,183.3\n1,1.0,21.0,160.0");
+    /// ```
+    pub fn to_csv_opts(&self, filename: &str, na_rep: &str, precision: Option<usize>, index: bool, index_label: Option<String>) {
+        let format = |c: &Series, i: usize, precision: Option<usize>| -> String {
+            let x = c.iloc(i);
+            if c.is_string() { c.field(i) }
+            else if x.is_nan() { na_rep.to_string() }
+            else if let Some(p) = precision { format!("{:.*}", p, x) }
+            else { x.to_string() }
+        };
+
+        // The row labels are written like any other column except for `precision`, positions if
+        // there aren't any
+        let positions = Series::from(0..self.rows.len() as i32);
+        let mut cols: Vec<&Series> = vec![];
+        let mut header_row: Vec<String> = vec![];
+        if index {
+            cols.push(self.index.as_ref().unwrap_or(&positions));
+            header_row.push(index_label.unwrap_or_default());
+        }
+        cols.extend(&self.cols);
+        header_row.extend(self.header_row.iter().cloned());
+
+        let header: String = header_row.into_iter().map(quote_field).collect::<Vec<String>>().join(",") + "\n";
+        let out: Vec<String> = (0..self.rows.len()).into_par_iter().map(|i| {
+            cols.iter()
+                .enumerate()
+                .map(|(j, c)| quote_field(if index && j == 0 { format(c, i, None) } else { format(c, i, precision) }))
+                .collect::<Vec<String>>()
+                .join(",")
+        }).collect();
         fs::write(filename, header + &out.join("\n")).expect("Unable to write to file");
     }
 
@@ -582,7 +909,63 @@ impl DataFrame {
         HashMap::from_par_iter(zipped)
     }
 
-    /// Extracts the first N rows of the DataFrame
+    /// Serializes the DataFrame as JSON, keyed by the header
+    ///
+    /// With `"records"` orient this is an array holding an object per row, and with `"columns"`
+    /// it is an object holding an array per column. JSON has no NaN, so missing values become
+    /// null, and string columns are written as strings
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and convert it to JSON both ways
+    /// | UserID |  Age  |
+    /// |   0    |   42  |
+    /// |   1    |  NaN  |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0]),
+    ///     Series::new(vec![42.0, f64::NAN])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// assert_eq!(
+    ///     df.to_json("records"),
+    ///     r#"[{"UserID": 0.0, "Age": 42.0}, {"UserID": 1.0, "Age": null}]"#
+    /// );
+    /// assert_eq!(df.to_json("columns"), r#"{"UserID": [0.0, 1.0], "Age": [42.0, null]}"#);
+    /// ```
+    pub fn to_json(&self, orient: &str) -> String {
+        let value = |col: &Series, i: usize| {
+            if col.is_string() { json::string(&col.field(i)) } else { json::number(col.iloc(i)) }
+        };
+
+        match orient {
+            "records" => {
+                let records: Vec<String> = (0..self.rows.len()).into_par_iter()
+                    .map(|i| {
+                        let entries: Vec<(String, String)> = self.header_row.iter().zip(&self.cols)
+                            .map(|(h, col)| (h.clone(), value(col, i)))
+                            .collect();
+                        json::object(&entries)
+                    })
+                    .collect();
+                json::array(&records)
+            },
+            "columns" => {
+                let entries: Vec<(String, String)> = self.header_row.par_iter().zip(&self.cols)
+                    .map(|(h, col)| {
+                        let values: Vec<String> = (0..col.size()).map(|i| value(col, i)).collect();
+                        (h.clone(), json::array(&values))
+                    })
+                    .collect();
+                json::object(&entries)
+            },
+            _ => panic!("Unknown orient {}", orient)
+        }
+    }
+
+    /// Extracts the first N rows of the DataFrame, or all of them if there are fewer than N
     ///
     /// # Examples
     ///
@@ -601,18 +984,14 @@ impl DataFrame {
     /// ];
     /// let df: DataFrame = DataFrame::new(data, Some(header));
     /// println!("{}", df.head(2));
+    /// assert_eq!(df.head(1000), df);
     /// ```
     pub fn head(&self, n: usize) -> DataFrame {
-        let sliced = (&self.cols).into_par_iter()
-            .map(|x| {
-                x.slice(0, n)
-            })
-            .collect();
-
-        DataFrame::new(sliced, Some(self.header_row.clone()))
+        let n = n.min(self.rows.len());
+        self.islice(0, n)
     }
 
-    /// Extracts the last N rows of the DataFrame
+    /// Extracts the last N rows of the DataFrame, or all of them if there are fewer than N
     ///
     /// # Examples
     ///
@@ -630,141 +1009,1372 @@ impl DataFrame {
     ///     Series::new(vec![183.0, 160.0, 132.0])
     /// ];
     /// let df: DataFrame = DataFrame::new(data, Some(header));
-    /// println!("{}", df.head(2));
+    /// println!("{}", df.tail(2));
+    /// assert_eq!(df.tail(1000), df);
     /// ```
     pub fn tail(&self, n: usize) -> DataFrame {
-        let sliced = (&self.cols).into_par_iter()
-            .map(|x| {
-                x.slice(x.size() - n, x.size())
-            })
-            .collect();
-
-        DataFrame::new(sliced, Some(self.header_row.clone()))
-    }
-
-    /// Adds a value to all elements in the DataFrame
-    pub fn plus(&self, n: f64) -> DataFrame {
-        // One would think this would be a good opportunity to *apply* our apply but since Rust
-        // won't allow us to capture variables, we can't pass in a func that uses n...
-        let header = Some(self.header_row.clone());
-        let applied = (&self.cols).into_par_iter()
-            .map(|x| x.plus(n))
-            .collect();
-        DataFrame::new(applied, header)
-    }
-
-    /// Subtracts a value to all elements in the DataFrame
-    pub fn sub(&self, n: f64) -> DataFrame {
-        let header = Some(self.header_row.clone());
-        let applied = (&self.cols).into_par_iter()
-            .map(|x| x.sub(n))
-            .collect();
-        DataFrame::new(applied, header)
+        let n = n.min(self.rows.len());
+        self.islice(self.rows.len() - n, self.rows.len())
     }
 
-    /// Multiplies a value to all elements in the DataFrame
-    pub fn mult(&self, n: f64) -> DataFrame {
-        let header = Some(self.header_row.clone());
-        let applied = (&self.cols).iter()
-            .map(|x| x.mult(n))
-            .collect();
-        DataFrame::new(applied, header)
-    }
+    /// Extracts the rows from position `start` up to (but not including) `end`. Like
+    /// `Series::slice`, a range running past the last row is cut short rather than panicking
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and extract the middle two rows
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// |   3    |   35  |  170   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0, 3.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0, 35.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0, 170.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// assert_eq!(df.islice(1, 3).loc_col("UserID").unwrap(), Series::new(vec![1.0, 2.0]));
+    /// assert_eq!(df.islice(3, 10).loc_col("UserID").unwrap(), Series::new(vec![3.0]));
+    /// ```
+    pub fn islice(&self, start: usize, end: usize) -> DataFrame {
+        let end = end.min(self.rows.len());
+        let start = start.min(end);
+        let sliced = self.cols.par_iter().map(|x| x.slice(start, end)).collect();
+        let index = self.index.as_ref().map(|labels| labels.slice(start, end));
 
-    /// Divides a value to all elements in the DataFrame
-    pub fn div(&self, n: f64) -> DataFrame {
-        let header = Some(self.header_row.clone());
-        let applied = (&self.cols).into_par_iter()
-            .map(|x| x.div(n))
-            .collect();
-        DataFrame::new(applied, header)
+        DataFrame::new(sliced, Some(self.header_row.clone())).with_index(index)
     }
 
-    /// Computes the cumulative/prefix sum for each Series in the DataFrame
+    /// Sorts the rows of the DataFrame by the values of a column, keeping rows together. Rows
+    /// with a missing value in that column go at the end either way
     ///
     /// # Examples
     ///
-    /// Create a new DataFrame of the form and compute the cumulative sum
+    /// Create a new DataFrame of the form and sort it from oldest to youngest
     /// | UserID |  Age  | Height |
     /// |   0    |   42  |  183   |
-    /// |   1    |   21  |  160   |
+    /// |   1    |   NaN |  160   |
     /// |   2    |   8   |  132   |
     /// ```
     ///
     /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
     /// let data: Vec<Series> = vec![
     ///     Series::new(vec![0.0, 1.0, 2.0]),
-    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![42.0, f64::NAN, 8.0]),
     ///     Series::new(vec![183.0, 160.0, 132.0])
     /// ];
     /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let sorted: DataFrame = df.sort_values("Age", true);
+    /// assert_eq!(sorted.loc_col("UserID").unwrap(), Series::new(vec![2.0, 0.0, 1.0]));
+    /// assert_eq!(sorted.loc_col("Height").unwrap(), Series::new(vec![132.0, 183.0, 160.0]));
+    /// ```
+    pub fn sort_values(&self, by: &str, ascending: bool) -> DataFrame {
+        let order = self.cols[self.col_idx(by)].argsort(ascending);
+        self.take_rows(&order)
+    }
+
+    /// Finds the `n` rows with the largest values in a column, largest first. Rows with a missing
+    /// value in that column only make it in if there aren't enough other rows
     ///
-    /// // Over columns
-    /// println!("{}", df.cumsum(0));
+    /// # Examples
     ///
-    /// // Over rows
-    /// println!("{}", df.cumsum(1));
+    /// Create a new DataFrame of the form and find the two oldest users
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   NaN |  160   |
+    /// |   2    |   8   |  132   |
+    /// |   3    |   21  |  150   |
     /// ```
-    pub fn cumsum(&self, axis: usize) -> DataFrame {
-        parse_axis!(self, cumsum, axis)
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0, 3.0]),
+    ///     Series::new(vec![42.0, f64::NAN, 8.0, 21.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0, 150.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let oldest: DataFrame = df.nlargest(2, "Age");
+    /// assert_eq!(oldest.loc_col("UserID").unwrap(), Series::new(vec![0.0, 3.0]));
+    /// ```
+    pub fn nlargest(&self, n: usize, by: &str) -> DataFrame {
+        let order = self.cols[self.col_idx(by)].argsort(false);
+        self.take_rows(&order[..n.min(order.len())])
     }
 
-    /// Returns a new DataFrame with a new column inserted into it 
+    /// Finds the `n` rows with the smallest values in a column, smallest first. Rows with a
+    /// missing value in that column only make it in if there aren't enough other rows
     ///
     /// # Examples
     ///
-    /// Create a new DataFrame of the form and adds a column for Weight
+    /// Create a new DataFrame of the form and find the two shortest users
     /// | UserID |  Age  | Height |
     /// |   0    |   42  |  183   |
-    /// |   1    |   21  |  160   |
+    /// |   1    |   NaN |  160   |
     /// |   2    |   8   |  132   |
+    /// |   3    |   21  |  150   |
     /// ```
     ///
     /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
     /// let data: Vec<Series> = vec![
-    ///     Series::new(vec![0.0, 1.0, 2.0]),
-    ///     Series::new(vec![42.0, 21.0, 8.0]),
-    ///     Series::new(vec![183.0, 160.0, 132.0])
+    ///     Series::new(vec![0.0, 1.0, 2.0, 3.0]),
+    ///     Series::new(vec![42.0, f64::NAN, 8.0, 21.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0, 150.0])
     /// ];
-    ///
-    /// let weight_data: Series = Series::new(vec![100.0, 300.0, 77.0]);
     /// let df: DataFrame = DataFrame::new(data, Some(header));
-    /// let df = df.insert_col(3, "Weight" weight_data);
-    /// println!("{}", df);
+    /// let shortest: DataFrame = df.nsmallest(2, "Height");
+    /// assert_eq!(shortest.loc_col("UserID").unwrap(), Series::new(vec![2.0, 3.0]));
     /// ```
-    pub fn insert_col(&self, pos: usize, column_name: &str, column: Series) -> DataFrame {
-        // Suckier than normal insertion since we are creating an entirely new DataFrame
-        if pos > self.cols.len() + 1 { panic!("Invalid index"); }
-        let mut cols = self.cols.clone();
-        let mut headers = self.header_row.clone();
-        cols.insert(pos, column);
-        headers.insert(pos, column_name.to_string());
-        let size = cols.len() * self.rows.len();
+    pub fn nsmallest(&self, n: usize, by: &str) -> DataFrame {
+        let order = self.cols[self.col_idx(by)].argsort(true);
+        self.take_rows(&order[..n.min(order.len())])
+    }
 
-        DataFrame {
-            header_row: headers,
-            cols,
-            rows: self.rows.clone(),
-            size
-        }
+    /// Adds a value to all elements in the DataFrame
+    pub fn plus(&self, n: f64) -> DataFrame {
+        self.map_cols(|x| x.plus(n))
+    }
+
+    /// Subtracts a value to all elements in the DataFrame
+    pub fn sub(&self, n: f64) -> DataFrame {
+        self.map_cols(|x| x.sub(n))
+    }
+
+    /// Multiplies a value to all elements in the DataFrame
+    pub fn mult(&self, n: f64) -> DataFrame {
+        self.map_cols(|x| x.mult(n))
+    }
+
+    /// Divides a value to all elements in the DataFrame
+    pub fn div(&self, n: f64) -> DataFrame {
+        self.map_cols(|x| x.div(n))
+    }
+
+    /// Adds a Series to every row (axis 0) or to every column (axis 1) of the DataFrame
+    ///
+    /// The Series must have one value per column for axis 0 and one value per row for axis 1,
+    /// otherwise this panics
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and add a bias to each column, then to each row
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    ///
+    /// // Across rows
+    /// let biased: DataFrame = df.add_series(Series::new(vec![1.0, 0.0, -100.0]), 0);
+    /// assert_eq!(biased.irow(0), Series::new(vec![1.0, 42.0, 83.0]));
+    ///
+    /// // Down columns
+    /// let biased: DataFrame = df.add_series(Series::new(vec![10.0, 20.0, 30.0]), 1);
+    /// assert_eq!(biased.loc_col("Age").unwrap(), Series::new(vec![52.0, 41.0, 38.0]));
+    /// ```
+    pub fn add_series(&self, s: Series, axis: usize) -> DataFrame {
+        let header = Some(self.header_row.clone());
+        let expected = if axis == 0 { self.cols.len() } else { self.rows.len() };
+        if s.size() != expected { panic!("Series must have same dimensions"); }
+
+        let applied = if axis == 0 {
+            (&self.cols).into_par_iter()
+                .enumerate()
+                .map(|(i, x)| x.plus(s.iloc(i)))
+                .collect()
+        }
+        else {
+            (&self.cols).into_par_iter()
+                .map(|x| x.vadd(s.clone()))
+                .collect()
+        };
+        DataFrame::new(applied, header)
+    }
+
+    /// Computes the cumulative/prefix sum for each Series in the DataFrame
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and compute the cumulative sum
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    ///
+    /// // Over columns
+    /// println!("{}", df.cumsum(0));
+    ///
+    /// // Over rows
+    /// println!("{}", df.cumsum(1));
+    /// ```
+    pub fn cumsum(&self, axis: usize) -> DataFrame {
+        parse_axis!(self, cumsum, axis)
+    }
+
+    /// Ranks the values of each Series in the DataFrame over an axis, with ties getting their
+    /// average rank and NaNs staying NaN
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and rank each column
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   21  |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 21.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    ///
+    /// // Over columns
+    /// assert_eq!(df.rank(0).loc_col("Age").unwrap(), Series::new(vec![3.0, 1.5, 1.5]));
+    ///
+    /// // Over rows
+    /// println!("{}", df.rank(1));
+    /// ```
+    pub fn rank(&self, axis: usize) -> DataFrame {
+        parse_axis!(self, rank, axis)
+    }
+
+    /// Applies a Python function to each trailing window of `window` values in every column
+    /// (axis 0) or every row (axis 1). The function is passed the window as a list of floats and
+    /// must return a float; the first `window - 1` positions don't have a full window and are NaN.
+    /// A window of 0 raises a `ValueError`
+    ///
+    /// The function is called once per window while holding the GIL, so this is much slower
+    /// than the built-in methods and is only meant for statistics we don't have in Rust
+    ///
+    /// # Examples
+    ///
+    /// Compute the spread of each pair of consecutive values from Python
+    /// ```python
+    /// from rusty_pandas import DataFrame, Series
+    ///
+    /// df = DataFrame([Series([1.0, 2.0, 4.0, 8.0]), Series([3.0, 3.0, 0.0, 1.0])], ["a", "b"])
+    /// spread = df.rolling_apply(2, lambda w: max(w) - min(w), 0)
+    /// assert spread.loc_col("a").to_vec()[1:] == [1.0, 2.0, 4.0]
+    /// assert spread.loc_col("b").to_vec()[1:] == [0.0, 3.0, 1.0]
+    /// ```
+    pub fn rolling_apply(&self, window: usize, func: &PyAny, axis: usize) -> PyResult<DataFrame> {
+        if window == 0 { return Err(PyValueError::new_err("Window size must be positive")) }
+
+        let series = if axis == 0 { &self.cols } else { &self.rows };
+        let applied = series.iter()
+            .map(|s| rolling_apply_series(s, window, func))
+            .collect::<PyResult<Vec<Series>>>()?;
+
+        if axis == 0 { Ok(DataFrame::new(applied, Some(self.header_row.clone()))) }
+        else { Ok(DataFrame::from_rows(applied, self.header_row.clone())) }
+    }
+
+    /// Returns a new DataFrame with a new column inserted into it at `pos`, which can be anywhere
+    /// from 0 (the front) up to the number of columns (the back). Any other position panics
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and adds a column for Weight
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    ///
+    /// let weight_data: Series = Series::new(vec![100.0, 300.0, 77.0]);
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let df = df.insert_col(3, "Weight", weight_data.clone());
+    /// assert_eq!(df.loc_col("Weight").unwrap(), weight_data);
+    /// assert_eq!(df.irow(0), Series::new(vec![0.0, 42.0, 183.0, 100.0]));
+    ///
+    /// let front = df.insert_col(0, "Index", Series::new(vec![5.0, 6.0, 7.0]));
+    /// assert_eq!(front.icol(0), Series::new(vec![5.0, 6.0, 7.0]));
+    /// ```
+    pub fn insert_col(&self, pos: usize, column_name: &str, column: Series) -> DataFrame {
+        // Suckier than normal insertion since we are creating an entirely new DataFrame
+        if pos > self.cols.len() {
+            panic!("Invalid position {} to insert into a DataFrame with {} columns, expected 0 to {}", pos, self.cols.len(), self.cols.len());
+        }
+        let mut cols = self.cols.clone();
+        let mut headers = self.header_row.clone();
+        cols.insert(pos, column);
+        headers.insert(pos, column_name.to_string());
+
+        // Rebuilt from the columns so the rows pick up the new column too
+        DataFrame::new(cols, Some(headers)).with_index(self.index.clone())
+    }
+
+    /// Returns a new DataFrame with a row appended to it. If the rows are labelled (see
+    /// `set_index`), the new row is labelled by its position
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and add another user
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let df = df.append_row(Series::new(vec![3.0, 35.0, 170.0]));
+    /// assert_eq!(df.irow(3), Series::new(vec![3.0, 35.0, 170.0]));
+    /// assert_eq!(df.loc_col("Age").unwrap(), Series::new(vec![42.0, 21.0, 8.0, 35.0]));
+    ///
+    /// // Row labels are kept, and the new row is labelled by its position
+    /// let df = df.set_index("UserID").append_row(Series::new(vec![50.0, 175.0]));
+    /// assert_eq!(df.loc_row("4").unwrap(), Series::new(vec![50.0, 175.0]));
+    /// assert_eq!(df.loc_row("3").unwrap(), Series::new(vec![35.0, 170.0]));
+    /// ```
+    pub fn append_row(&self, row: Series) -> DataFrame {
+        if row.size() != self.cols.len() { panic!("Row must have one value per column"); }
+        let cols = self.cols.iter()
+            .zip(row.iter())
+            .map(|(col, &x)| col.clone() + Series::new(vec![x]))
+            .collect();
+
+        // The new row is labelled by its position, keeping the text of a string index
+        let pos = self.rows.len();
+        let index = self.index.as_ref().map(|labels| {
+            let mut data = labels.to_vec();
+            data.push(pos as f64);
+            if labels.is_string() {
+                let mut strings = labels.to_strings();
+                strings.push(pos.to_string());
+                Series::with_strings(data, strings)
+            } else {
+                Series::new(data)
+            }
+        });
+
+        DataFrame::new(cols, Some(self.header_row.clone())).with_index(index)
+    }
+
+    /// Returns a new DataFrame without the row at the given position
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and drop the second row
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let dropped: DataFrame = df.drop_row(1);
+    /// assert_eq!(dropped.loc_col("UserID").unwrap(), Series::new(vec![0.0, 2.0]));
+    /// ```
+    pub fn drop_row(&self, idx: usize) -> DataFrame {
+        if idx >= self.rows.len() { panic!("Invalid index {} for a DataFrame with {} rows", idx, self.rows.len()); }
+        let keep: Vec<usize> = (0..self.rows.len()).filter(|&i| i != idx).collect();
+        self.take_rows(&keep)
+    }
+
+    /// Returns a new DataFrame without the column at the given position
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and drop the second column
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let dropped: DataFrame = df.drop_col_idx(1);
+    /// assert_eq!(dropped.irow(0), Series::new(vec![0.0, 183.0]));
+    /// ```
+    pub fn drop_col_idx(&self, idx: usize) -> DataFrame {
+        if idx >= self.cols.len() { panic!("Invalid index"); }
+        let mut cols = self.cols.clone();
+        let mut headers = self.header_row.clone();
+        cols.remove(idx);
+        headers.remove(idx);
+
+        DataFrame::new(cols, Some(headers)).with_index(self.index.clone())
+    }
+
+    /// Returns a new DataFrame without the named column. If there is no column by that name, the
+    /// DataFrame is returned unchanged
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and drop the Age column
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let dropped: DataFrame = df.drop_col("Age");
+    /// assert_eq!(dropped.loc_col("Age"), None);
+    /// assert_eq!(df.drop_col("Weight"), df);
+    /// ```
+    pub fn drop_col(&self, name: &str) -> DataFrame {
+        match self.header_row.iter().position(|h| h == name) {
+            Some(idx) => self.drop_col_idx(idx),
+            None => self.copy()
+        }
+    }
+
+    /// Returns a new DataFrame holding only the named columns, in the order they are given
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and keep only the Height and UserID columns
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let selected: DataFrame = df.select(vec!["Height".to_string(), "UserID".to_string()]);
+    /// assert_eq!(selected.irow(0), Series::new(vec![183.0, 0.0]));
+    /// ```
+    pub fn select(&self, names: Vec<String>) -> DataFrame {
+        let cols = names.iter().map(|name| self.cols[self.col_idx(name)].clone()).collect();
+        DataFrame::new(cols, Some(names)).with_index(self.index.clone())
+    }
+
+    /// Returns a new DataFrame holding only the numeric columns, i.e. without the string columns
+    /// (see `Series::is_string`), so statistics aren't computed over labels
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and keep only the numeric columns
+    /// | UserID |  Name  | Height |
+    /// |   0    |  Ann   |  183   |
+    /// |   1    |  Bob   |  160   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Name".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0]),
+    ///     Series::from_strings(vec!["Ann".to_string(), "Bob".to_string()]),
+    ///     Series::new(vec![183.0, 160.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let numeric: DataFrame = df.numeric_cols();
+    /// assert!(numeric.loc_col("Name").is_none());
+    /// assert_eq!(numeric.mean(0).irow(0), Series::new(vec![0.5, 171.5]));
+    /// ```
+    pub fn numeric_cols(&self) -> DataFrame {
+        let names = self.header_row.iter().zip(&self.cols)
+            .filter(|(_, col)| !col.is_string())
+            .map(|(name, _)| name.clone())
+            .collect();
+        self.select(names)
+    }
+
+    /// Returns a new DataFrame holding only the rows where the mask is non-zero. A NaN in the mask
+    /// drops its row
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and keep only the adults
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let adults: DataFrame = df.filter(df.loc_col("Age").unwrap().ge(18.0));
+    /// assert_eq!(adults.loc_col("UserID").unwrap(), Series::new(vec![0.0, 1.0]));
+    /// ```
+    pub fn filter(&self, mask: Series) -> DataFrame {
+        if mask.size() != self.rows.len() { panic!("Mask must have one value per row"); }
+        let keep: Vec<usize> = mask.iter()
+            .enumerate()
+            .filter(|(_, &m)| m != 0.0 && !m.is_nan())
+            .map(|(i, _)| i)
+            .collect();
+
+        self.take_rows(&keep)
     }
     
+    /// Computes count/mean/std/min/max of every other column for each group of a key column
+    ///
+    /// The result has one row per distinct (non-NaN) key value, in ascending order, followed by
+    /// a `<column>_<statistic>` block for each of the remaining columns
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and summarise the scores of each group
+    /// | Group | Score |
+    /// |   1   |  10   |
+    /// |   2   |  20   |
+    /// |   1   |  30   |
+    /// |   2   |  40   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["Group".to_string(), "Score".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![1.0, 2.0, 1.0, 2.0]),
+    ///     Series::new(vec![10.0, 20.0, 30.0, 40.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let described = df.describe_by("Group");
+    /// assert_eq!(described.loc_col("Group").unwrap(), Series::new(vec![1.0, 2.0]));
+    /// assert_eq!(described.loc_col("Score_mean").unwrap(), Series::new(vec![20.0, 30.0]));
+    /// ```
+    pub fn describe_by(&self, key_col: &str) -> DataFrame {
+        let key_idx = self.col_idx(key_col);
+        let others: Vec<usize> = (0..self.cols.len()).filter(|&i| i != key_idx).collect();
+
+        let mut header = vec![key_col.to_string()];
+        for &i in &others {
+            header.extend(Series::SUMMARY_STATS.iter().map(|stat| format!("{}_{}", self.header_row[i], stat)));
+        }
+
+        let rows: Vec<Series> = self.cols[key_idx].group_indices().par_iter()
+            .map(|(key, idx)| {
+                let mut row = vec![*key];
+                for &i in &others {
+                    row.extend(self.cols[i].take(idx).summary());
+                }
+                Series::new(row)
+            })
+            .collect();
+
+        DataFrame::from_rows(rows, header)
+    }
+
+    /// Groups the rows by the values of a column and aggregates every other column within each
+    /// group using one of `"sum"`, `"mean"`, `"min"`, `"max"` or `"count"`
+    ///
+    /// The result has one row per distinct (non-NaN) key value, in ascending order. Missing
+    /// values are skipped when aggregating, and a group with none left aggregates to NaN (or 0
+    /// for `"sum"` and `"count"`)
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and total the scores of each group
+    /// | Group | Score |
+    /// |   1   |  10   |
+    /// |   2   |  20   |
+    /// |   1   |  30   |
+    /// |   2   |  NaN  |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["Group".to_string(), "Score".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![1.0, 2.0, 1.0, 2.0]),
+    ///     Series::new(vec![10.0, 20.0, 30.0, f64::NAN])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let grouped = df.groupby("Group", "sum");
+    /// assert_eq!(grouped.loc_col("Group").unwrap(), Series::new(vec![1.0, 2.0]));
+    /// assert_eq!(grouped.loc_col("Score").unwrap(), Series::new(vec![40.0, 20.0]));
+    /// assert_eq!(df.groupby("Group", "count").loc_col("Score").unwrap(), Series::new(vec![2.0, 1.0]));
+    /// ```
+    pub fn groupby(&self, key: &str, agg: &str) -> DataFrame {
+        let aggregate: fn(&Series) -> f64 = match agg {
+            "sum" => |s| s.sum().iloc(0),
+            "mean" => |s| s.dropna().mean().iter().next().copied().unwrap_or(f64::NAN),
+            "min" => |s| s.min().iter().next().copied().unwrap_or(f64::NAN),
+            "max" => |s| s.max().iter().next().copied().unwrap_or(f64::NAN),
+            "count" => |s| s.count() as f64,
+            _ => panic!("Unknown aggregation {}", agg)
+        };
+
+        let key_idx = self.col_idx(key);
+        let others: Vec<usize> = (0..self.cols.len()).filter(|&i| i != key_idx).collect();
+
+        let mut header = vec![key.to_string()];
+        header.extend(others.iter().map(|&i| self.header_row[i].clone()));
+
+        let rows: Vec<Series> = self.cols[key_idx].group_indices().par_iter()
+            .map(|(value, idx)| {
+                let mut row = vec![*value];
+                row.extend(others.iter().map(|&i| aggregate(&self.cols[i].take(idx))));
+                Series::new(row)
+            })
+            .collect();
+
+        DataFrame::from_rows(rows, header)
+    }
+
+    /// Reshapes long data into wide form: each distinct value of `index` becomes a row and each
+    /// distinct value of `columns` becomes a column (named by its formatted value, e.g. 3.0 as
+    /// `"3"`), with the cells filled from `values`
+    ///
+    /// Rows and columns come out in ascending order of their key and the first column holds the
+    /// `index` keys. Combinations that never appear are NaN, rows with a NaN key are dropped and
+    /// a combination that appears twice panics
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and pivot the readings of each sensor into columns
+    /// | Day | Sensor | Reading |
+    /// |  1  |   10   |   0.5   |
+    /// |  1  |   20   |   0.7   |
+    /// |  2  |   10   |   0.6   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["Day".to_string(), "Sensor".to_string(), "Reading".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![1.0, 1.0, 2.0]),
+    ///     Series::new(vec![10.0, 20.0, 10.0]),
+    ///     Series::new(vec![0.5, 0.7, 0.6])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let wide: DataFrame = df.pivot("Day", "Sensor", "Reading");
+    /// assert_eq!(wide.loc_col("Day").unwrap(), Series::new(vec![1.0, 2.0]));
+    /// assert_eq!(wide.loc_col("10").unwrap(), Series::new(vec![0.5, 0.6]));
+    /// assert!(wide.loc_col("20").unwrap().iloc(1).is_nan());
+    /// ```
+    pub fn pivot(&self, index: &str, columns: &str, values: &str) -> DataFrame {
+        let values = &self.cols[self.col_idx(values)];
+        let row_groups = self.cols[self.col_idx(index)].group_indices();
+        let col_groups = self.cols[self.col_idx(columns)].group_indices();
+
+        // Which output row and column each input row lands in, if its keys aren't NaN
+        let position = |groups: &Vec<(f64, Vec<usize>)>| {
+            let mut pos = vec![None; self.rows.len()];
+            for (g, (_, idx)) in groups.iter().enumerate() {
+                for &i in idx { pos[i] = Some(g); }
+            }
+            pos
+        };
+        let (row_pos, col_pos) = (position(&row_groups), position(&col_groups));
+
+        let mut cells = vec![vec![f64::NAN; row_groups.len()]; col_groups.len()];
+        let mut filled = vec![vec![false; row_groups.len()]; col_groups.len()];
+        for i in 0..self.rows.len() {
+            if let (Some(r), Some(c)) = (row_pos[i], col_pos[i]) {
+                if filled[c][r] {
+                    panic!("Duplicate entry for {} {} and {} {}", index, row_groups[r].0, columns, col_groups[c].0);
+                }
+                cells[c][r] = values.iloc(i);
+                filled[c][r] = true;
+            }
+        }
+
+        let mut header = vec![index.to_string()];
+        header.extend(col_groups.iter().map(|(key, _)| key.to_string()));
+        let mut cols = vec![Series::new(row_groups.iter().map(|(key, _)| *key).collect())];
+        cols.extend(cells.into_iter().map(Series::new));
+
+        DataFrame::new(cols, Some(header))
+    }
+
+    /// Unpivots wide data into long form, the inverse of `pivot`. Each of the `value_vars`
+    /// columns is stacked into a `value` column, with a string `variable` column naming the
+    /// column it came from and the `id_vars` columns repeated alongside
+    ///
+    /// If `value_vars` is empty every column that isn't in `id_vars` is stacked. The rows come
+    /// out column by column, i.e. all of the first column's values before the second's
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and melt the sensor columns
+    /// | Day |  10  |  20  |
+    /// |  1  |  0.5 |  0.7 |
+    /// |  2  |  0.6 |  0.8 |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["Day".to_string(), "10".to_string(), "20".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![1.0, 2.0]),
+    ///     Series::new(vec![0.5, 0.6]),
+    ///     Series::new(vec![0.7, 0.8])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let long: DataFrame = df.melt(vec!["Day".to_string()], vec![]);
+    /// assert_eq!(long.loc_col("Day").unwrap(), Series::new(vec![1.0, 2.0, 1.0, 2.0]));
+    /// assert_eq!(long.loc_col("variable").unwrap().to_strings(), vec!["10", "10", "20", "20"]);
+    /// assert_eq!(long.loc_col("value").unwrap(), Series::new(vec![0.5, 0.6, 0.7, 0.8]));
+    /// ```
+    pub fn melt(&self, id_vars: Vec<String>, value_vars: Vec<String>) -> DataFrame {
+        let id_idx: Vec<usize> = id_vars.iter().map(|name| self.col_idx(name)).collect();
+        let value_idx: Vec<usize> = if value_vars.is_empty() {
+            (0..self.cols.len()).filter(|i| !id_idx.contains(i)).collect()
+        }
+        else {
+            value_vars.iter().map(|name| self.col_idx(name)).collect()
+        };
+
+        let n = self.rows.len();
+        let repeated: Vec<usize> = (0..value_idx.len()).flat_map(|_| 0..n).collect();
+        let variable: Vec<String> = value_idx.iter()
+            .flat_map(|&i| std::iter::repeat_n(self.header_row[i].clone(), n))
+            .collect();
+        let value = value_idx.iter().fold(Series::zero(), |acc, &i| acc + self.cols[i].clone());
+
+        let mut header: Vec<String> = id_idx.iter().map(|&i| self.header_row[i].clone()).collect();
+        header.extend(["variable".to_string(), "value".to_string()]);
+        let mut cols: Vec<Series> = id_idx.iter().map(|&i| self.cols[i].take(&repeated)).collect();
+        cols.extend([Series::from_strings(variable), value]);
+
+        DataFrame::new(cols, Some(header))
+    }
+
+    /// Joins two DataFrames on a column they share, pairing up every row of this DataFrame with
+    /// every row of `other` that has the same value in that column. Only `"inner"` joins are
+    /// supported for now
+    ///
+    /// The result holds the key column followed by the remaining columns of this DataFrame and
+    /// then those of `other`, ordered by key. Column names found in both get an `_x` (this
+    /// DataFrame) or `_y` (`other`) suffix, and NaN keys never match
+    ///
+    /// # Examples
+    ///
+    /// Create two DataFrames of the form and join them on their UserID
+    /// | UserID |  Age  |       | UserID | Height |
+    /// |   0    |   42  |       |   2    |  132   |
+    /// |   1    |   21  |       |   0    |  183   |
+    /// |   2    |   8   |       |   5    |  170   |
+    /// ```
+    ///
+    /// let left: DataFrame = DataFrame::new(
+    ///     vec![Series::new(vec![0.0, 1.0, 2.0]), Series::new(vec![42.0, 21.0, 8.0])],
+    ///     Some(vec!["UserID".to_string(), "Age".to_string()])
+    /// );
+    /// let right: DataFrame = DataFrame::new(
+    ///     vec![Series::new(vec![2.0, 0.0, 5.0]), Series::new(vec![132.0, 183.0, 170.0])],
+    ///     Some(vec!["UserID".to_string(), "Height".to_string()])
+    /// );
+    /// let merged: DataFrame = left.merge(&right, "UserID", "inner");
+    /// assert_eq!(merged.loc_col("UserID").unwrap(), Series::new(vec![0.0, 2.0]));
+    /// assert_eq!(merged.loc_col("Age").unwrap(), Series::new(vec![42.0, 8.0]));
+    /// assert_eq!(merged.loc_col("Height").unwrap(), Series::new(vec![183.0, 132.0]));
+    /// ```
+    pub fn merge(&self, other: &DataFrame, on: &str, how: &str) -> DataFrame {
+        if how != "inner" { panic!("Unsupported join {}", how) }
+
+        let (left_key, right_key) = (self.col_idx(on), other.col_idx(on));
+        let left_groups = self.cols[left_key].group_indices();
+        let right_groups = other.cols[right_key].group_indices();
+
+        // Both sides are grouped in ascending order of key, so walk them together
+        let (mut left_idx, mut right_idx) = (Vec::new(), Vec::new());
+        let (mut i, mut j) = (0, 0);
+        while i < left_groups.len() && j < right_groups.len() {
+            let (left_value, left_rows) = &left_groups[i];
+            let (right_value, right_rows) = &right_groups[j];
+            if left_value < right_value { i += 1 }
+            else if left_value > right_value { j += 1 }
+            else {
+                for &l in left_rows {
+                    for &r in right_rows {
+                        left_idx.push(l);
+                        right_idx.push(r);
+                    }
+                }
+                i += 1;
+                j += 1;
+            }
+        }
+
+        let left_others: Vec<usize> = (0..self.cols.len()).filter(|&c| c != left_key).collect();
+        let right_others: Vec<usize> = (0..other.cols.len()).filter(|&c| c != right_key).collect();
+        let name = |df: &DataFrame, c: usize, against: &DataFrame, against_others: &[usize], suffix: &str| {
+            let h = &df.header_row[c];
+            if against_others.iter().any(|&a| &against.header_row[a] == h) { format!("{}_{}", h, suffix) } else { h.clone() }
+        };
+
+        let mut header = vec![on.to_string()];
+        let mut cols = vec![self.cols[left_key].take(&left_idx)];
+        for &c in &left_others {
+            header.push(name(self, c, other, &right_others, "x"));
+            cols.push(self.cols[c].take(&left_idx));
+        }
+        for &c in &right_others {
+            header.push(name(other, c, self, &left_others, "y"));
+            cols.push(other.cols[c].take(&right_idx));
+        }
+
+        DataFrame::new(cols, Some(header))
+    }
+
+    /// Summarises every column with the statistics of `Series::describe`, one statistic per row
+    ///
+    /// The first column, `statistic`, is a string column naming each row's statistic: `count` (of
+    /// non-NaN values), `mean`, `std`, `min`, the `25%`, `50%` and `75%` quantiles and `max`. It is
+    /// followed by one column per column of the DataFrame
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and describe it
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  NaN   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, f64::NAN])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let described: DataFrame = df.describe();
+    /// assert_eq!(described.loc_col("statistic").unwrap().to_strings()[4], "25%".to_string());
+    /// assert_eq!(described.loc_col("Height").unwrap().iloc(0), 2.0);
+    /// assert_eq!(described.loc_col("Age").unwrap().iloc(7), 42.0);
+    /// ```
+    pub fn describe(&self) -> DataFrame {
+        let mut header = vec!["statistic".to_string()];
+        header.extend(self.header_row.iter().cloned());
+
+        let mut cols = vec![Series::from_strings(Series::DESCRIBE_STATS.iter().map(|s| s.to_string()).collect())];
+        cols.extend(self.cols.par_iter().map(|col| col.describe().irow(0)).collect::<Vec<Series>>());
+
+        DataFrame::new(cols, Some(header))
+    }
+
+    /// Counts the occurrences of each distinct value in a column as a DataFrame with a `value`
+    /// and a `count` column, like `Series::value_counts` (NaNs aren't counted)
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and count the ages
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   42  |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 42.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let counts: DataFrame = df.value_counts("Age");
+    /// assert_eq!(counts.loc_col("value").unwrap(), Series::new(vec![42.0, 21.0]));
+    /// assert_eq!(counts.loc_col("count").unwrap(), Series::new(vec![2.0, 1.0]));
+    /// ```
+    pub fn value_counts(&self, col: &str) -> DataFrame {
+        self.cols[self.col_idx(col)].value_counts(true)
+    }
+
+    /// Computes the Pearson correlation between every pair of columns as a square matrix, where
+    /// row `i` and column `i` both belong to the `i`th column of the DataFrame
+    ///
+    /// Each pair drops the positions where either column is NaN, so cells can be computed from
+    /// different numbers of rows. The diagonal is 1.0 unless the column's correlation with itself
+    /// is undefined (e.g. it is constant), in which case it is NaN
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and correlate its columns
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   0   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 0.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let corr: DataFrame = df.corr();
+    /// assert_eq!(corr.loc_col("UserID").unwrap(), corr.irow(0));
+    /// assert_eq!(corr.loc_col("Age").unwrap().iloc(0), -1.0);
+    /// assert_eq!(corr.loc_col("Height").unwrap().iloc(2), 1.0);
+    /// ```
+    pub fn corr(&self) -> DataFrame {
+        self.pairwise_matrix(|i, j| {
+            let r = self.cols[i].corr(self.cols[j].clone()).iloc(0);
+            if i == j && !r.is_nan() { 1.0 } else { r }
+        })
+    }
+
+    /// Computes the sample covariance between every pair of columns as a square matrix, where
+    /// row `i` and column `i` both belong to the `i`th column of the DataFrame
+    ///
+    /// Each pair drops the positions where either column is NaN, so the diagonal holds the
+    /// variance of each column
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and find the covariances of its columns
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   0   |  NaN   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 0.0]),
+    ///     Series::new(vec![183.0, 160.0, f64::NAN])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let cov: DataFrame = df.cov();
+    /// assert_eq!(cov.loc_col("UserID").unwrap(), Series::new(vec![1.0, -21.0, -11.5]));
+    /// assert_eq!(cov.loc_col("Age").unwrap(), cov.irow(1));
+    /// ```
+    pub fn cov(&self) -> DataFrame {
+        self.pairwise_matrix(|i, j| self.cols[i].cov(self.cols[j].clone()).iloc(0))
+    }
+
+    /// Computes count/mean/std/min/max of every column as a tidy, long-format DataFrame
+    ///
+    /// Each row is `(column, statistic, value)` where `column` is the column's position and
+    /// `statistic` is a string column holding `count`, `mean`, `std`, `min` or `max`
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and compute the statistics of every column
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let stats: DataFrame = df.column_stats();
+    /// assert_eq!(stats.icol(0).size(), 3 * 5);
+    /// assert_eq!(stats.loc_col("statistic").unwrap().to_strings()[1], "mean".to_string());
+    /// ```
+    pub fn column_stats(&self) -> DataFrame {
+        let n_stats = Series::SUMMARY_STATS.len();
+        let column: Vec<f64> = (0..self.cols.len()).flat_map(|i| vec![i as f64; n_stats]).collect();
+        let statistic: Vec<String> = (0..self.cols.len())
+            .flat_map(|_| Series::SUMMARY_STATS.iter().map(|s| s.to_string()))
+            .collect();
+        let value: Vec<f64> = self.cols.par_iter().flat_map_iter(|col| col.summary()).collect();
+
+        let header = vec!["column".to_string(), "statistic".to_string(), "value".to_string()];
+        DataFrame::new(vec![Series::new(column), Series::from_strings(statistic), Series::new(value)], Some(header))
+    }
+
+    /// Summarises the data quality of every column as one row per column holding its position,
+    /// the number of non-missing values, the fraction of missing values, the number of distinct
+    /// values, the min/max/mean of the non-missing values and whether the column is constant
+    ///
+    /// A column counts as constant (1.0) if it has at most one distinct value, ignoring NaNs
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and check the quality of every column
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  NaN   |
+    /// |   1    |   42  |  160   |
+    /// |   2    |   42  |  NaN   |
+    /// |   3    |   42  |  150   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0, 3.0]),
+    ///     Series::new(vec![42.0, 42.0, 42.0, 42.0]),
+    ///     Series::new(vec![f64::NAN, 160.0, f64::NAN, 150.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let report: DataFrame = df.quality_report();
+    /// assert_eq!(report.loc_col("null_fraction").unwrap(), Series::new(vec![0.0, 0.0, 0.5]));
+    /// assert_eq!(report.loc_col("distinct").unwrap(), Series::new(vec![4.0, 1.0, 2.0]));
+    /// assert_eq!(report.loc_col("is_constant").unwrap(), Series::new(vec![0.0, 1.0, 0.0]));
+    /// ```
+    pub fn quality_report(&self) -> DataFrame {
+        let rows: Vec<Series> = self.cols.par_iter()
+            .enumerate()
+            .map(|(i, col)| {
+                let non_null = col.count();
+                let distinct = col.nunique();
+                let stats = col.summary();
+                Series::new(vec![
+                    i as f64,
+                    non_null as f64,
+                    (col.size() - non_null) as f64 / col.size() as f64,
+                    distinct as f64,
+                    stats[3],
+                    stats[4],
+                    stats[1],
+                    (distinct <= 1) as i32 as f64
+                ])
+            })
+            .collect();
+
+        let header = ["column", "non_null", "null_fraction", "distinct", "min", "max", "mean", "is_constant"]
+            .iter().map(|h| h.to_string()).collect();
+        DataFrame::from_rows(rows, header)
+    }
+
+    /// Splits the row indices into `k` disjoint, shuffled folds for cross-validation
+    ///
+    /// Every row lands in exactly one fold and fold sizes differ by at most one. Passing a seed
+    /// makes the folds reproducible
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and split its rows into three folds
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let folds: Vec<Vec<usize>> = df.kfold_indices(3, Some(42));
+    /// assert_eq!(folds, df.kfold_indices(3, Some(42)));
+    ///
+    /// let mut all: Vec<usize> = folds.concat();
+    /// all.sort();
+    /// assert_eq!(all, vec![0, 1, 2]);
+    /// ```
+    pub fn kfold_indices(&self, k: usize, seed: Option<u64>) -> Vec<Vec<usize>> {
+        let n = self.rows.len();
+        if k == 0 || k > n { panic!("Number of folds must be between 1 and the number of rows ({})", n); }
+
+        let mut rng = match seed {
+            Some(s) => StdRng::seed_from_u64(s),
+            None => StdRng::from_entropy()
+        };
+        let mut indices: Vec<usize> = (0..n).collect();
+        indices.shuffle(&mut rng);
+
+        (0..k).map(|i| indices[i * n / k..(i + 1) * n / k].to_vec()).collect()
+    }
+
     /// Generates the default header row
     #[staticmethod]
     fn gen_default_header(len: usize) -> Vec<String> {
         (0..len).into_par_iter().map(|x| x.to_string()).collect()
     }
 
-    fn __str__(&self) -> &'static str {
-        let out: String = self.header_row.iter().zip(&self.cols).map(|(h, d)| format!("{h}: {d}")).collect::<Vec<String>>().join(", ");
-        Box::leak(out.into_boxed_str())
+    // Returned as an owned String so Python frees it, printing in a loop doesn't grow memory
+    fn __str__(&self) -> String {
+        self.header_row.iter().zip(&self.cols).map(|(h, d)| format!("{h}: {d}")).collect::<Vec<String>>().join(", ")
+    }
+    fn __repr__(&self) -> String {
+        self.__str__()
+    }
+}
+
+// Helpers that PyO3 can't (or shouldn't) expose
+impl DataFrame {
+    /// Builds a DataFrame from its rows rather than its columns
+    pub(crate) fn from_rows(rows: Vec<Series>, header_row: Vec<String>) -> DataFrame {
+        let cols = transpose(&rows);
+        let size = rows.len() * cols.len();
+
+        DataFrame {
+            header_row,
+            cols,
+            rows,
+            size,
+            index: None
+        }
+    }
+
+    /// Finds the position of a column by name, panicking if there isn't one
+    pub(crate) fn col_idx(&self, col_name: &str) -> usize {
+        self.header_row.iter().position(|c| c == col_name)
+            .unwrap_or_else(|| panic!("No column named {}", col_name))
+    }
+
+    /// Builds a symmetric column-by-column matrix from `f(i, j)`, only computing the upper
+    /// triangle (in parallel) and mirroring it
+    pub(crate) fn pairwise_matrix(&self, f: impl Fn(usize, usize) -> f64 + Sync + Send) -> DataFrame {
+        let n = self.cols.len();
+        let upper: Vec<(usize, usize, f64)> = (0..n).into_par_iter()
+            .flat_map_iter(|i| (i..n).map(move |j| (i, j)))
+            .map(|(i, j)| (i, j, f(i, j)))
+            .collect();
+
+        let mut matrix = vec![vec![f64::NAN; n]; n];
+        for (i, j, value) in upper {
+            matrix[i][j] = value;
+            matrix[j][i] = value;
+        }
+
+        DataFrame::new(matrix.into_iter().map(Series::new).collect(), Some(self.header_row.clone()))
+    }
+
+    /// Gathers the rows at the given positions into a new DataFrame
+    pub(crate) fn take_rows(&self, idx: &[usize]) -> DataFrame {
+        let cols = self.cols.par_iter().map(|c| c.take(idx)).collect();
+        let index = self.index.as_ref().map(|labels| labels.take(idx));
+        DataFrame::new(cols, Some(self.header_row.clone())).with_index(index)
+    }
+
+    /// Replaces the row labels (see `set_index`)
+    pub(crate) fn with_index(mut self, index: Option<Series>) -> DataFrame {
+        self.index = index;
+        self
+    }
+
+    /// Aggregates each Series in the DataFrame with a user-defined function that may return
+    /// several values, e.g. both the minimum and the maximum. Every result must be the same length
+    ///
+    /// Like the built-in aggregations, axis 0 keeps the header and axis 1 gives one column per row
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and calculate the range of each column
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let min_max = |s: &Series| -> Series { Series::new(vec![s.min().iloc(0), s.max().iloc(0)]) };
+    ///
+    /// let ranges: DataFrame = df.agg_custom(min_max, 0);
+    /// assert_eq!(ranges.loc_col("Age").unwrap(), Series::new(vec![8.0, 42.0]));
+    /// assert_eq!(ranges.loc_col("Height").unwrap(), Series::new(vec![132.0, 183.0]));
+    /// ```
+    pub fn agg_custom(&self, f: fn(&Series) -> Series, axis: usize) -> DataFrame {
+        let (series, header) = if axis == 0 { (&self.cols, Some(self.header_row.clone())) } else { (&self.rows, None) };
+        let results: Vec<Series> = if series.len() < Series::par_threshold() {
+            series.iter().map(f).collect()
+        }
+        else {
+            series.par_iter().map(f).collect()
+        };
+
+        if results.windows(2).any(|w| w[0].size() != w[1].size()) {
+            panic!("Aggregation results must have same dimensions");
+        }
+        DataFrame::new(results, header)
+    }
+
+    /// Like `agg_custom`, but skips every column (or row) with a missing value like `dropna`
+    /// does, without building the intermediate DataFrame (and its transpose) first. Along axis 1
+    /// this is `f` of each column over the complete rows, which is what `dropna(1)` followed by
+    /// the row-wise aggregation works out to
+    pub(crate) fn agg_complete(&self, f: fn(&Series) -> Series, axis: usize) -> DataFrame {
+        let complete = |s: &Series| s.count() == s.size();
+        if axis == 0 {
+            let (results, header): (Vec<Series>, Vec<String>) = self.cols.par_iter().zip(self.header_row.par_iter())
+                .filter(|(s, _)| complete(s))
+                .map(|(s, h)| (f(s), h.clone()))
+                .unzip();
+            DataFrame::new(results, Some(header))
+        }
+        else {
+            let keep: Vec<usize> = (0..self.rows.len()).filter(|&i| complete(&self.rows[i])).collect();
+            if keep.is_empty() { return DataFrame::new(vec![], None) }
+            DataFrame::new(self.cols.par_iter().map(|s| f(&s.take(&keep))).collect(), None)
+        }
+    }
+
+    /// Applies a function to all values inside the DataFrame, keeping the header
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and divide all by 10
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let f = |x: f64| -> f64 { x / 10.0 };
+    /// assert_eq!(df.apply_fn(f), df.div(10.0));
+    /// ```
+    pub fn apply_fn(&self, f: fn(f64) -> f64) -> DataFrame {
+        self.map_cols(|x| x.map_values(f))
+    }
+
+    /// Applies a whole-Series transform to every column in parallel, keeping the header. The
+    /// transformed columns must all have the same length
+    ///
+    /// # Examples
+    ///
+    /// Create a new DataFrame of the form and normalize each column
+    /// | UserID |  Age  | Height |
+    /// |   0    |   42  |  183   |
+    /// |   1    |   21  |  160   |
+    /// |   2    |   8   |  132   |
+    /// ```
+    ///
+    /// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+    /// let data: Vec<Series> = vec![
+    ///     Series::new(vec![0.0, 1.0, 2.0]),
+    ///     Series::new(vec![42.0, 21.0, 8.0]),
+    ///     Series::new(vec![183.0, 160.0, 132.0])
+    /// ];
+    /// let df: DataFrame = DataFrame::new(data, Some(header));
+    /// let normalized: DataFrame = df.map_cols(|s| s.normalize());
+    /// assert_eq!(normalized.loc_col("UserID").unwrap(), Series::new(vec![0.0, 0.5, 1.0]));
+    /// ```
+    pub fn map_cols(&self, f: impl Fn(&Series) -> Series + Sync + Send) -> DataFrame {
+        let mapped: Vec<Series> = self.cols.par_iter().map(f).collect();
+
+        if mapped.windows(2).any(|w| w[0].size() != w[1].size()) {
+            panic!("Mapped columns must have same dimensions");
+        }
+        DataFrame::new(mapped, Some(self.header_row.clone()))
+    }
+}
+
+/// Calls a Python function on each trailing window of a Series (see `DataFrame::rolling_apply`)
+fn rolling_apply_series(s: &Series, window: usize, func: &PyAny) -> PyResult<Series> {
+    let values = s.to_vec();
+    let mut applied = vec![f64::NAN; values.len()];
+    for i in window.saturating_sub(1)..values.len() {
+        applied[i] = func.call1((values[i + 1 - window..=i].to_vec(),))?.extract()?;
+    }
+    Ok(Series::new(applied))
+}
+
+/// Parses a CSV field as a number, stripping any thousands separators first. Anything that
+/// doesn't parse becomes NaN
+fn parse_field(field: &str, thousands: Option<char>) -> f64 {
+    parse_number(field, thousands).unwrap_or(f64::NAN)
+}
+
+/// Parses a CSV field as a number, giving None if it isn't one
+fn parse_number(field: &str, thousands: Option<char>) -> Option<f64> {
+    let parsed = match thousands {
+        Some(sep) => field.replace(sep, "").parse::<f64>(),
+        None => field.parse::<f64>()
+    };
+    parsed.ok()
+}
+
+/// The values of a CSV column, which stay as text unless every non-empty one is a number
+enum ColumnData {
+    Floats(Vec<f64>),
+    Strings(Vec<String>)
+}
+
+impl ColumnData {
+    fn parse(fields: &[&str], thousands: Option<char>) -> ColumnData {
+        // Empty fields are just missing values, so they don't make a column textual
+        let numeric = fields.iter().all(|f| f.trim().is_empty() || parse_number(f, thousands).is_some());
+
+        if numeric { ColumnData::Floats(fields.iter().map(|f| parse_field(f, thousands)).collect()) }
+        else { ColumnData::Strings(fields.iter().map(|f| f.to_string()).collect()) }
     }
-    fn __repr__(&self) -> &'static str {
-        let out: String = self.header_row.iter().zip(&self.cols).map(|(h, d)| format!("{h}: {d}")).collect::<Vec<String>>().join(", ");
-        Box::leak(out.into_boxed_str())
+
+    fn into_series(self, thousands: Option<char>) -> Series {
+        match self {
+            ColumnData::Floats(data) => Series::new(data),
+            ColumnData::Strings(strings) => {
+                let data = strings.iter().map(|f| parse_field(f, thousands)).collect();
+                Series::with_strings(data, strings)
+            }
+        }
     }
 }
 
+/// Turns the JSON values of a column into a Series (see `read_json`)
+fn json_column(values: Vec<json::Value>) -> Series {
+    let data: Vec<f64> = values.iter()
+        .map(|v| if let json::Value::Number(x) = v { *x } else { f64::NAN })
+        .collect();
+    if !values.iter().any(|v| matches!(v, json::Value::String(_))) { return Series::new(data) }
+
+    let strings = values.into_iter()
+        .map(|v| match v {
+            json::Value::String(s) => s,
+            json::Value::Number(x) => x.to_string(),
+            json::Value::Bool(b) => b.to_string(),
+            _ => String::new()
+        })
+        .collect();
+    Series::with_strings(data, strings)
+}
+
 /// Transposes a vector of Series
 fn transpose(mat: &Vec<Series>) -> Vec<Series> {
     if mat.len() == 0 { return mat.to_vec() }
@@ -777,46 +2387,416 @@ fn transpose(mat: &Vec<Series>) -> Vec<Series> {
     }).collect()
 }
 
+/// Splits the contents of a CSV file into records, keeping newlines inside quoted fields as
+/// part of their record
+fn split_records(contents: &str) -> Vec<&str> {
+    // Without any quotes every line is a record, so we can split in parallel
+    if !contents.contains('"') { return contents.par_lines().collect() }
+
+    let mut records = vec![];
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, b) in contents.bytes().enumerate() {
+        match b {
+            b'"' => in_quotes = !in_quotes,
+            b'\n' if !in_quotes => {
+                records.push(contents[start..i].trim_end_matches('\r'));
+                start = i + 1;
+            },
+            _ => ()
+        }
+    }
+    if start < contents.len() { records.push(contents[start..].trim_end_matches('\r')); }
+    records
+}
+
+/// Quotes a CSV field if it would otherwise be split up when read back in, doubling any quotes
+/// inside it (RFC 4180)
+fn quote_field(field: String) -> String {
+    if field.contains([',', '"', '\n', '\r']) { format!("\"{}\"", field.replace('"', "\"\"")) } else { field }
+}
+
+/// Splits a CSV record into fields, ignoring delimiters inside quoted fields. A quoted field
+/// loses its surrounding pair of quotes and has its doubled quotes unescaped (RFC 4180)
+fn split_fields(record: &str, delimiter: char) -> Vec<Cow<'_, str>> {
+    // split has better performance than par_split here
+    if !record.contains('"') { return record.split(delimiter).map(Cow::Borrowed).collect() }
+
+    let mut fields = vec![];
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, c) in record.char_indices() {
+        if c == '"' { in_quotes = !in_quotes; }
+        else if c == delimiter && !in_quotes {
+            fields.push(&record[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    fields.push(&record[start..]);
+    fields.into_iter().map(unquote_field).collect()
+}
+
+/// Removes one surrounding pair of quotes from a CSV field and turns `""` inside it back into `"`.
+/// Quotes in a field that isn't quoted as a whole are part of the data and stay
+fn unquote_field(field: &str) -> Cow<'_, str> {
+    match field.strip_prefix('"').and_then(|f| f.strip_suffix('"')) {
+        Some(inner) if inner.contains("\"\"") => Cow::Owned(inner.replace("\"\"", "\"")),
+        Some(inner) => Cow::Borrowed(inner),
+        None => Cow::Borrowed(field)
+    }
+}
+
 /// Reads a CSV file into a DataFrame
 ///
+/// Fields may be quoted, in which case they can contain commas and newlines
+///
 /// # Examples
 /// ```
 /// let df: DataFrame = dataframe::read_csv("example.csv");
 /// println!("{}", df);
+///
+/// // The comment column isn't numeric, so it keeps its text
+/// let df: DataFrame = dataframe::read_csv("res/typed.csv");
+/// assert_eq!(df.loc_col("comment").unwrap().to_strings(), vec!["hello", "world", "again"]);
+///
+/// // One of the quoted fields spans two lines but it's still only three records
+/// let df: DataFrame = dataframe::read_csv("res/quoted_newline.csv");
+/// assert_eq!(df.icol(0).size(), 3usize);
 /// ```
 #[pyfunction]
 pub fn read_csv(filename: &str) -> DataFrame {
+    read_csv_opts(filename, ',', true, None, 0, None, vec![])
+}
+
+/// Reads a CSV file into a DataFrame, splitting fields on `delimiter`
+///
+/// If `has_header` is false the first line is data too, and the columns are named by position
+/// like a DataFrame created without a header. The first `skip_rows` lines are dropped before
+/// anything else (e.g. to get rid of a banner above the header), as are any lines starting with
+/// `comment`, so the header is the first line that's left.
+///
+/// Fields that are exactly one of `na_values` (e.g. `"NA"` or `"-999"`) are missing values, as
+/// are empty fields, even if they'd otherwise parse as numbers. They are compared as text, so
+/// `"-999"` doesn't catch `-999.0`. If `thousands` is given, that separator is stripped from each field before it is parsed.
+/// With a comma delimiter, numbers like `1,234.5` have to be quoted to be read as one field
+///
+/// A column with any non-empty field that isn't a number is kept as a string column (see
+/// `Series::from_strings`) rather than being turned into NaNs
+///
+/// From Python every option can be left out or passed by keyword, and defaults to what
+/// `read_csv` uses: a comma delimiter with a header, no thousands separator, no skipped or
+/// commented lines and no extra missing value markers
+///
+/// # Examples
+/// ```
+/// // The prices are quoted with comma thousands separators, e.g. "1,234.5"
+/// let df: DataFrame = dataframe::read_csv_opts("res/thousands.csv", ',', true, Some(','), 0, None, vec![]);
+/// assert_eq!(df.loc_col("price").unwrap(), Series::new(vec![1234.5, 12000.0, 7.25]));
+///
+/// // A tab separated file without a header
+/// let df: DataFrame = dataframe::read_csv_opts("res/no_header.tsv", '\t', false, None, 0, None, vec![]);
+/// assert_eq!(df.loc_col("1").unwrap(), Series::new(vec![2.0, 5.0]));
+///
+/// // Two banner lines sit above the header, and some lines are commented out with #
+/// let df: DataFrame = dataframe::read_csv_opts("res/banner.csv", ',', true, None, 2, Some('#'), vec![]);
+/// assert_eq!(df.loc_col("count").unwrap(), Series::new(vec![10.0, 30.0]));
+///
+/// // Missing readings are written as NA or -999
+/// let na_values: Vec<String> = vec!["NA".to_string(), "-999".to_string()];
+/// let df: DataFrame = dataframe::read_csv_opts("res/na_values.csv", ',', true, None, 0, None, na_values);
+/// assert_eq!(df.loc_col("reading").unwrap().fillna(0.0), Series::new(vec![1.5, 0.0, 0.0, 2.0, 0.0]));
+/// ```
+#[pyfunction(delimiter = "','", has_header = "true", thousands = "None", skip_rows = "0", comment = "None", na_values = "Vec::new()")]
+pub fn read_csv_opts(
+    filename: &str,
+    delimiter: char,
+    has_header: bool,
+    thousands: Option<char>,
+    skip_rows: usize,
+    comment: Option<char>,
+    na_values: Vec<String>
+) -> DataFrame {
     // Read the entire file to a String
     let file = fs::read_to_string(filename).expect("Something went wrong when reading");
-    // Split into lines
-    let lines: Vec<&str> = file.par_lines().collect();
+    parse_csv(&file, delimiter, has_header, thousands, skip_rows, comment, &na_values)
+}
+
+/// Reads a CSV file into a DataFrame like `read_csv`, but returns an error instead of panicking
+/// if the file can't be read. In Python the error is raised as an `OSError`
+///
+/// # Examples
+/// ```
+/// assert!(dataframe::try_read_csv("res/typed.csv").is_ok());
+/// assert!(dataframe::try_read_csv("res/missing.csv").is_err());
+/// ```
+#[pyfunction]
+pub fn try_read_csv(filename: &str) -> std::io::Result<DataFrame> {
+    let file = fs::read_to_string(filename)?;
+    Ok(parse_csv(&file, ',', true, None, 0, None, &[]))
+}
+
+/// Parses the contents of a CSV file (see `read_csv_opts`)
+fn parse_csv(
+    file: &str,
+    delimiter: char,
+    has_header: bool,
+    thousands: Option<char>,
+    skip_rows: usize,
+    comment: Option<char>,
+    na_values: &[String]
+) -> DataFrame {
+    let mut file = file;
+    for _ in 0..skip_rows {
+        file = file.split_once('\n').map_or("", |(_, rest)| rest);
+    }
+    // Split into records (quoted fields may span several lines)
+    let mut lines: Vec<&str> = split_records(file);
+    if let Some(c) = comment { lines.retain(|line| !line.starts_with(c)); }
+    if lines.is_empty() { return DataFrame::new(vec![], None) }
+    let data_lines = if has_header { &lines[1..] } else { &lines[..] };
+    let records: Vec<Vec<Cow<str>>> = data_lines.into_par_iter().map(|line| split_fields(line, delimiter)).collect();
     // Extract header row
-    let header_row: Vec<String> = (&lines[0]).par_split(',').map(|x| String::from(x)).collect();
-    // Parse data into numeric values
-    let data: Vec<Series> = (&lines[1..]).into_par_iter().map(|line| {
-        Series::new(
-            line.split(",").map(|elt| { // split has better performance than par_split here
-                match elt.parse::<f64>() {
-                    Ok(f) => f,
-                    Err(_) => f64::NAN
+    let header_row: Vec<String> = if has_header {
+        parse_header(lines[0], delimiter)
+    }
+    else {
+        DataFrame::gen_default_header(records.iter().map(|r| r.len()).max().unwrap_or(0))
+    };
+    let data = parse_columns(&records, header_row.len(), thousands, na_values);
+
+    DataFrame::new(data, Some(header_row))
+}
+
+/// Splits the header line of a CSV file into column names
+fn parse_header(line: &str, delimiter: char) -> Vec<String> {
+    split_fields(line, delimiter).into_iter().map(Cow::into_owned).collect()
+}
+
+/// Parses the fields of each record into `n_cols` columns, as numbers or kept as text if a
+/// column isn't numeric. Records missing a field are padded with missing values
+fn parse_columns(records: &[Vec<Cow<str>>], n_cols: usize, thousands: Option<char>, na_values: &[String]) -> Vec<Series> {
+    (0..n_cols).into_par_iter().map(|c| {
+        // Missing values are all made empty, so NA markers don't turn a column into text
+        let fields: Vec<&str> = records.iter()
+            .map(|r| r.get(c).map(|f| f.as_ref()).unwrap_or(""))
+            .map(|f| if na_values.iter().any(|na| na == f) { "" } else { f })
+            .collect();
+        ColumnData::parse(&fields, thousands).into_series(thousands)
+    }).collect()
+}
+
+/// The parsing options of `read_csv_opts`, for the readers that take them in one go. The
+/// default is what `read_csv` uses
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub has_header: bool,
+    pub thousands: Option<char>,
+    pub skip_rows: usize,
+    pub comment: Option<char>,
+    pub na_values: Vec<String>
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions { delimiter: ',', has_header: true, thousands: None, skip_rows: 0, comment: None, na_values: vec![] }
+    }
+}
+
+/// Reads a CSV file like `read_csv_opts`, but a chunk of up to `chunk_rows` rows at a time,
+/// calling `f` with each chunk as a DataFrame (all with the file's header). Only one chunk is
+/// held in memory at once, so this works on files too big to load whole
+///
+/// Each chunk is parsed on its own, so a column can be a string column in one chunk and numeric
+/// in the next (see `read_csv_opts`). Without a header the number of columns is taken from the
+/// first record rather than the widest one
+///
+/// # Examples
+/// ```
+/// // Total a column without loading the whole file
+/// let mut total: f64 = 0.0;
+/// let mut chunks: usize = 0;
+/// dataframe::read_csv_chunked("res/quoted_newline.csv", 2, &CsvOptions::default(), |chunk| {
+///     total += chunk.loc_col("value").unwrap().sum().iloc(0);
+///     chunks += 1;
+/// });
+/// assert_eq!(total, 60.0);
+/// assert_eq!(chunks, 2);
+///
+/// // The same options as read_csv_opts, e.g. skipping a banner and commented out lines
+/// let opts = CsvOptions { skip_rows: 2, comment: Some('#'), ..CsvOptions::default() };
+/// dataframe::read_csv_chunked("res/banner.csv", 10, &opts, |chunk| {
+///     assert_eq!(chunk.loc_col("count").unwrap(), Series::new(vec![10.0, 30.0]));
+/// });
+/// ```
+pub fn read_csv_chunked(filename: &str, chunk_rows: usize, opts: &CsvOptions, mut f: impl FnMut(DataFrame)) {
+    if chunk_rows == 0 { panic!("chunk_rows must be positive"); }
+
+    let file = fs::File::open(filename).expect("Something went wrong when reading");
+    let mut lines = BufReader::new(file).lines()
+        .map(|line| line.expect("Something went wrong when reading"))
+        .skip(opts.skip_rows);
+
+    // Like split_records, a quoted field may span several lines, so keep joining lines until
+    // the quotes are balanced
+    let comment = opts.comment;
+    let mut next_record = move || -> Option<String> {
+        loop {
+            let mut record = lines.next()?;
+            while record.matches('"').count() % 2 == 1 {
+                match lines.next() {
+                    Some(line) => { record.push('\n'); record.push_str(&line); },
+                    None => break
                 }
-            }).collect()
-        )
+            }
+            if comment.is_some_and(|c| record.starts_with(c)) { continue; }
+            return Some(record.trim_end_matches('\r').to_string())
+        }
+    };
+
+    let first = match next_record() {
+        Some(line) => line,
+        None => return
+    };
+    // Without a header the first record is data, so it goes in the first chunk
+    let (header_row, mut pending) = if opts.has_header {
+        (parse_header(&first, opts.delimiter), None)
+    }
+    else {
+        (DataFrame::gen_default_header(split_fields(&first, opts.delimiter).len()), Some(first))
+    };
+
+    loop {
+        let chunk: Vec<String> = pending.take().into_iter()
+            .chain(std::iter::from_fn(&mut next_record))
+            .take(chunk_rows)
+            .collect();
+        if chunk.is_empty() { break; }
+
+        let records: Vec<Vec<Cow<str>>> = chunk.par_iter().map(|line| split_fields(line, opts.delimiter)).collect();
+        f(DataFrame::new(parse_columns(&records, header_row.len(), opts.thousands, &opts.na_values), Some(header_row.clone())));
+        if chunk.len() < chunk_rows { break; }
+    }
+}
+
+/// Reads a CSV file into a DataFrame, parsing the named columns with the given types
+///
+/// Each type is one of `"f64"`, `"i64"` (values that aren't whole numbers become NaN) or
+/// `"skip"` (the column isn't loaded at all). Columns that aren't named are inferred like
+/// `read_csv` does, so text columns are kept as string columns
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// let mut types: HashMap<String, &str> = HashMap::new();
+/// types.insert("id".to_string(), "i64");
+/// types.insert("score".to_string(), "skip");
+///
+/// let df: DataFrame = dataframe::read_csv_typed("res/typed.csv", types);
+/// let ids: Series = df.loc_col("id").unwrap();
+/// assert_eq!(ids.iloc(0), 1.0);
+/// assert!(ids.iloc(1).is_nan());
+/// assert_eq!(ids.iloc(2), 3.0);
+/// assert!(df.loc_col("score").is_none());
+/// // The comment column has no type, so it's inferred as text
+/// assert_eq!(df.loc_col("comment").unwrap().to_strings(), vec!["hello", "world", "again"]);
+/// ```
+#[pyfunction]
+pub fn read_csv_typed(filename: &str, types: HashMap<String, &str>) -> DataFrame {
+    let file = fs::read_to_string(filename).expect("Something went wrong when reading");
+    let lines: Vec<&str> = split_records(&file);
+    if lines.is_empty() { return DataFrame::new(vec![], None) }
+
+    let all_headers: Vec<Cow<str>> = split_fields(lines[0], ',');
+    let col_types: Vec<Option<&str>> = all_headers.iter()
+        .map(|h| types.get(h.as_ref()).copied())
+        .collect();
+    if let Some(t) = col_types.iter().flatten().find(|t| !["f64", "i64", "skip"].contains(t)) {
+        panic!("Unknown column type {}", t);
+    }
+
+    let records: Vec<Vec<Cow<str>>> = (&lines[1..]).into_par_iter().map(|line| split_fields(line, ',')).collect();
+    let kept: Vec<usize> = (0..all_headers.len()).filter(|&c| col_types[c] != Some("skip")).collect();
+    let header_row: Vec<String> = kept.iter().map(|&c| all_headers[c].to_string()).collect();
+    let data: Vec<Series> = kept.par_iter().map(|&c| {
+        let fields: Vec<&str> = records.iter().map(|r| r.get(c).map(|f| f.as_ref()).unwrap_or("")).collect();
+        match col_types[c] {
+            // Parsed as f64 so whole numbers written like 1.0 or 1e3 are kept too
+            Some("i64") => Series::new(fields.iter()
+                .map(|f| f.parse::<f64>().ok().filter(|x| x.fract() == 0.0).unwrap_or(f64::NAN))
+                .collect()),
+            Some(_) => Series::new(fields.iter().map(|f| f.parse::<f64>().unwrap_or(f64::NAN)).collect()),
+            // No hint, so infer it the same way read_csv does
+            None => ColumnData::parse(&fields, None).into_series(None)
+        }
     }).collect();
 
-    // Transpose to get columns
-    let df_data = transpose(&data);
-    let size = data.len() * df_data.len();
+    DataFrame::new(data, Some(header_row))
+}
+
+/// Reads a JSON file written by `DataFrame::to_json` into a DataFrame
+///
+/// With `"records"` orient the file is an array of objects, one per row, and a key missing from
+/// a row is a missing value. With `"columns"` it is an object of arrays, one per column. A column
+/// holding any JSON strings becomes a string column, and other values that aren't numbers
+/// (null, booleans, ...) become NaN
+///
+/// # Examples
+/// ```
+/// let df: DataFrame = dataframe::read_json("res/records.json", "records");
+/// assert_eq!(df.loc_col("UserID").unwrap(), Series::new(vec![0.0, 1.0]));
+/// assert_eq!(df.loc_col("Name").unwrap().to_strings(), vec!["Ann", "Bob"]);
+/// assert!(df.loc_col("Age").unwrap().iloc(1).is_nan());
+/// ```
+#[pyfunction]
+pub fn read_json(filename: &str, orient: &str) -> DataFrame {
+    let file = fs::read_to_string(filename).expect("Something went wrong when reading");
+
+    let (header_row, columns): (Vec<String>, Vec<Vec<json::Value>>) = match (orient, json::parse(&file)) {
+        ("records", json::Value::Array(records)) => {
+            // Keys are taken in the order they first appear
+            let mut header_row: Vec<String> = vec![];
+            for record in &records {
+                let json::Value::Object(entries) = record else { panic!("Expected every record to be a JSON object") };
+                for (key, _) in entries {
+                    if !header_row.contains(key) { header_row.push(key.clone()); }
+                }
+            }
+
+            let columns = header_row.par_iter()
+                .map(|key| {
+                    records.iter()
+                        .map(|record| match record {
+                            json::Value::Object(entries) => entries.iter()
+                                .find(|(k, _)| k == key)
+                                .map_or(json::Value::Null, |(_, v)| v.clone()),
+                            _ => json::Value::Null
+                        })
+                        .collect()
+                })
+                .collect();
+            (header_row, columns)
+        },
+        ("columns", json::Value::Object(entries)) => entries.into_iter()
+            .map(|(key, values)| match values {
+                json::Value::Array(values) => (key, values),
+                _ => panic!("Expected column {} to be a JSON array", key)
+            })
+            .unzip(),
+        ("records", _) => panic!("Expected a JSON array of records"),
+        ("columns", _) => panic!("Expected a JSON object of columns"),
+        _ => panic!("Unknown orient {}", orient)
+    };
 
-    DataFrame {
-        header_row,
-        cols: df_data,
-        rows: data,
-        size
+    if columns.windows(2).any(|w| w[0].len() != w[1].len()) {
+        panic!("Series must have same dimensions");
     }
+    let data: Vec<Series> = columns.into_par_iter().map(json_column).collect();
+    DataFrame::new(data, Some(header_row))
 }
 
-/// Reads CSV files from a specified folder into a Vector of DataFrames
+/// Reads CSV files from a specified folder into a Vector of DataFrames, skipping any that can't
+/// be read
 ///
 /// # Examples
 /// ```
@@ -833,12 +2813,14 @@ pub fn read_csv_from_folder(folder_name: &str) -> Vec<DataFrame> {
         .collect();
 
     paths.par_iter()
-         .filter(|p| p.to_str().unwrap().ends_with(".csv"))
-         .map(|p| read_csv(p.to_str().unwrap()))
+         .filter_map(|p| p.to_str())
+         .filter(|p| p.ends_with(".csv"))
+         .filter_map(|p| try_read_csv(p).ok())
          .collect()
 }
 
-/// Reads CSV files whose names match a specified pattern into a Vector of DataFrames
+/// Reads CSV files whose names match a specified pattern into a Vector of DataFrames, skipping
+/// any that can't be read
 ///
 /// # Examples
 /// ```
@@ -853,10 +2835,57 @@ pub fn read_csv_by_glob(path: &str, expr: &str) -> Vec<DataFrame> {
         .map(|p| p.unwrap())
         .collect();
 
-    paths.into_par_iter()
-         .filter(|p| p.to_str().unwrap().ends_with(".csv"))
-         .map(|p| read_csv(p.to_str().unwrap()))
-         .collect()
+    paths.par_iter()
+         .filter_map(|p| p.to_str())
+         .filter(|p| p.ends_with(".csv"))
+         .filter_map(|p| try_read_csv(p).ok())
+         .collect()
+}
+
+/// Combines DataFrames into one, stacking their rows (axis 0) or placing their columns side by
+/// side (axis 1)
+///
+/// Stacking rows requires every DataFrame to have the same header, and placing columns side by
+/// side requires them all to have the same number of rows
+///
+/// # Examples
+/// ```
+/// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+/// let a: DataFrame = DataFrame::new(vec![Series::new(vec![0.0]), Series::new(vec![42.0])], Some(header.clone()));
+/// let b: DataFrame = DataFrame::new(vec![Series::new(vec![1.0]), Series::new(vec![21.0])], Some(header));
+/// let stacked: DataFrame = dataframe::concat(vec![a, b], 0);
+/// assert_eq!(stacked.loc_col("Age").unwrap(), Series::new(vec![42.0, 21.0]));
+/// ```
+#[pyfunction]
+pub fn concat(frames: Vec<DataFrame>, axis: usize) -> DataFrame {
+    let first = match frames.first() {
+        Some(first) => first,
+        None => return DataFrame::new(vec![], None)
+    };
+
+    if axis == 0 {
+        for df in &frames {
+            if df.header_row != first.header_row {
+                panic!("Header {:?} doesn't match {:?}", df.header_row, first.header_row)
+            }
+        }
+
+        let cols = (0..first.cols.len()).into_par_iter()
+            .map(|c| frames.iter().map(|df| df.cols[c].clone()).fold(Series::zero(), |acc, s| acc + s))
+            .collect();
+        DataFrame::new(cols, Some(first.header_row.clone()))
+    }
+    else {
+        for df in &frames {
+            if df.rows.len() != first.rows.len() {
+                panic!("DataFrame with header {:?} has {} rows but expected {}", df.header_row, df.rows.len(), first.rows.len())
+            }
+        }
+
+        let header = frames.iter().flat_map(|df| df.header_row.iter().cloned()).collect();
+        let cols = frames.into_iter().flat_map(|df| df.cols).collect();
+        DataFrame::new(cols, Some(header))
+    }
 }
 
 /// Creates a DataFrame from a HashMap
@@ -878,59 +2907,52 @@ pub fn from_hashmap(data_map: HashMap<String, Vec<f64>>) -> DataFrame {
     DataFrame::new(data, Some(header))
 }
 
+/// The positions shown along one dimension of a printed DataFrame: all of them if there are
+/// fewer than 10, otherwise the first and last 3 with a `None` marking the gap between them
+fn display_positions(len: usize) -> Vec<Option<usize>> {
+    if len < 10 { (0..len).map(Some).collect() }
+    else { (0..3).map(Some).chain([None]).chain((len - 3..len).map(Some)).collect() }
+}
+
+/// Formats the DataFrame as a table. A frame with 10 or more rows only shows the first and last
+/// 3 of them, and likewise for columns, so a tall or wide frame is only cut short the way it's large
+///
+/// # Example
+/// ```
+/// // Shows both columns but only 6 of the 100 rows, plus a row of "..."
+/// let tall: DataFrame = DataFrame::new(vec![Series::from(0..100), Series::from(100..200)], None);
+/// let printed: String = format!("{}", tall);
+/// assert_eq!(printed.lines().filter(|l| l.starts_with('|')).count(), 1 + 6 + 1);
+/// assert!(printed.contains(" 99 ") && printed.contains(" 199 "));
+///
+/// // Shows both rows but only 6 of the 100 columns, plus a column of "..."
+/// let wide: DataFrame = DataFrame::new((0..100).map(|i| Series::new(vec![i as f64, -i as f64])).collect(), None);
+/// let printed: String = format!("{}", wide);
+/// assert_eq!(printed.lines().filter(|l| l.starts_with('|')).count(), 1 + 2);
+/// assert!(printed.contains(" 99 ") && !printed.contains(" 50 "));
+/// ```
 impl Display for DataFrame {
     fn fmt(&self, f: &mut Formatter) -> Result {
         let mut table = Table::new();
 
-        if self.rows.len() < 10 && self.cols.len() < 10 {
-            table.add_row(Row::from(self.header_row.clone()));
-            for row in &self.rows {
-                let _ = table.add_row(Row::from(row.to_vec()));
-            }
+        // Only abbreviate the dimension that's actually large, so tall and wide frames both work
+        let col_pos = display_positions(self.cols.len());
+        let row_pos = display_positions(self.rows.len());
+
+        table.add_row(Row::from(col_pos.iter().map(|c| match c {
+            Some(j) => self.header_row[*j].clone(),
+            None => "...".to_string()
+        }).collect::<Vec<String>>()));
+
+        for r in &row_pos {
+            let cells: Vec<String> = col_pos.iter().map(|c| match (r, c) {
+                (Some(i), Some(j)) => self.cols[*j].field(*i),
+                _ => "...".to_string()
+            }).collect();
+            table.add_row(Row::from(cells));
         }
-        else {
-            let n = self.rows.len();
-            let mut header = vec![];
-            let mut dots = vec!["...".to_string()];
-
-            let mut header_start: Vec<String> = self.header_row[0..3].to_vec();
-            let mut header_end: Vec<String> = self.header_row[self.header_row.len() - 3..self.header_row.len()].to_vec();
-
-            header.extend(&mut header_start);
-            header.extend(&mut dots);
-            header.extend(&mut header_end);
-            
-            table.add_row(Row::from(header));
-
-            for row in &self.rows[0..3] {
-                let mut r: Vec<String> = vec![];
-                let m = row.size();
-                let start: Vec<String> = row.slice(0, 3).to_vec().iter().map(|x| format!("{x}")).collect();
-                let end: Vec<String> = row.slice(m-3, m).to_vec().iter().map(|x| format!("{x}")).collect();
-                r.extend(start);
-                r.extend(dots.clone());
-                r.extend(end);
-                table.add_row(Row::from(r));
-            }
-
-            table.add_row(Row::from(vec!["..."; 7]));
-
-            for row in &self.rows[n-3..n] {
-                let mut r: Vec<String> = vec![];
-                let m = row.size();
-                let start: Vec<String> = row.slice(0, 3).to_vec().iter().map(|x| format!("{x}")).collect();
-                let end: Vec<String> = row.slice(m-3, m).to_vec().iter().map(|x| format!("{x}")).collect();
-                r.extend(start);
-                r.extend(dots.clone());
-                r.extend(end);
-                table.add_row(Row::from(r));
-            }
-
 
-        }
-        //let out: Vec<String> = self.header_row.iter().zip(&self.cols).map(|(h, d)| format!("{h}: {d}")).collect();
-        table.printstd();
-        Ok(())
+        write!(f, "{}", table)
     }
 }
 
@@ -953,6 +2975,53 @@ impl Index<usize> for DataFrame {
     }
 }
 
+// Serialized as the header and the columns, the rows are rebuilt from the columns
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DataFrameRepr<H, C> {
+    header: H,
+    columns: C
+}
+
+/// Serializes the header and the columns (with the `serde` feature), with null in place of NaN.
+/// Deserializing fails if the columns differ in length or there isn't a header name per column
+///
+/// # Examples
+/// ```
+/// let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+/// let data: Vec<Series> = vec![Series::new(vec![0.0, 1.0]), Series::new(vec![42.0, f64::NAN])];
+/// let df: DataFrame = DataFrame::new(data, Some(header));
+///
+/// let json: String = serde_json::to_string(&df).unwrap();
+/// assert_eq!(json, r#"{"header":["UserID","Age"],"columns":[[0.0,1.0],[42.0,null]]}"#);
+///
+/// let restored: DataFrame = serde_json::from_str(&json).unwrap();
+/// assert_eq!(restored.loc_col("UserID").unwrap(), Series::new(vec![0.0, 1.0]));
+/// assert!(restored.loc_col("Age").unwrap().iloc(1).is_nan());
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for DataFrame {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        DataFrameRepr { header: &self.header_row, columns: &self.cols }.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DataFrame {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let repr: DataFrameRepr<Vec<String>, Vec<Series>> = serde::Deserialize::deserialize(deserializer)?;
+        if repr.header.len() != repr.columns.len() {
+            return Err(D::Error::custom(format!("{} header names for {} columns", repr.header.len(), repr.columns.len())));
+        }
+        if repr.columns.windows(2).any(|w| w[0].size() != w[1].size()) {
+            return Err(D::Error::custom("Series must have same dimensions"));
+        }
+        Ok(DataFrame::new(repr.columns, Some(repr.header)))
+    }
+}
+
 macro_rules! from_vec_type {
     ($type:ty) => {
         impl From<Vec<$type>> for DataFrame {
@@ -978,7 +3047,8 @@ macro_rules! from_2d_vec_type {
                    header_row: headers,
                    cols,
                    rows,
-                   size
+                   size,
+                   index: None
                 }
 
             }
@@ -1007,3 +3077,835 @@ from_2d_vec_type!(u8);
 from_2d_vec_type!(u16);
 from_2d_vec_type!(u32);
 from_2d_vec_type!(u64);
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+        let data: Vec<Series> = vec![Series::new(vec![0.0, 1.0]), Series::new(vec![42.0, f64::NAN])];
+        let df: DataFrame = DataFrame::new(data, Some(header));
+
+        let json: String = serde_json::to_string(&df).unwrap();
+        assert_eq!(json, r#"{"header":["UserID","Age"],"columns":[[0.0,1.0],[42.0,null]]}"#);
+
+        let restored: DataFrame = serde_json::from_str(&json).unwrap();
+        assert!(restored.equals(&df, 0.0));
+    }
+
+    #[test]
+    fn round_trip_string_column() {
+        let header: Vec<String> = vec!["id".to_string(), "name".to_string()];
+        let data: Vec<Series> = vec![Series::new(vec![0.0, 1.0]), Series::from_strings(vec!["a".to_string(), "b".to_string()])];
+        let df: DataFrame = DataFrame::new(data, Some(header));
+
+        let json: String = serde_json::to_string(&df).unwrap();
+        assert_eq!(json, r#"{"header":["id","name"],"columns":[[0.0,1.0],["a","b"]]}"#);
+
+        let restored: DataFrame = serde_json::from_str(&json).unwrap();
+        assert!(restored.loc_col("name").unwrap().is_string());
+
+        // The numeric view of the text survives too
+        let mixed: Series = serde_json::from_str(r#"["7", "x", null, 2.5]"#).unwrap();
+        assert_eq!(mixed.to_strings(), vec!["7", "x", "", "2.5"]);
+        assert_eq!(mixed.iloc(0), 7.0);
+        assert_eq!(mixed.iloc(3), 2.5);
+        assert_eq!(restored, df);
+    }
+
+    #[test]
+    fn rejects_ragged_columns() {
+        let json = r#"{"header":["a","b"],"columns":[[1.0],[1.0,2.0]]}"#;
+        assert!(serde_json::from_str::<DataFrame>(json).is_err());
+    }
+
+    #[test]
+    fn rejects_header_mismatch() {
+        let json = r#"{"header":["a"],"columns":[[1.0],[2.0]]}"#;
+        assert!(serde_json::from_str::<DataFrame>(json).is_err());
+    }
+}
+
+#[cfg(test)]
+mod display_tests {
+    use super::*;
+
+    #[test]
+    fn str_in_a_loop_gives_owned_equal_strings() {
+        let header: Vec<String> = vec!["a".to_string(), "b".to_string()];
+        let df: DataFrame = DataFrame::new(vec![Series::new(vec![1.0, 2.0]), Series::from_strings(vec!["x".to_string(), "y".to_string()])], Some(header));
+        let first: String = df.__str__();
+        assert_eq!(first, "a: [1.0, 2.0], b: [\"x\", \"y\"]");
+        for _ in 0..10_000 {
+            // An owned String that outlives the DataFrame it came from
+            let s: String = df.clone().__str__();
+            assert_eq!(s, first);
+        }
+        assert_eq!(df.__repr__(), first);
+    }
+
+    // The trimmed cells of the table's header and rows, skipping the separator lines
+    fn cells(df: &DataFrame) -> Vec<Vec<String>> {
+        format!("{}", df).lines()
+            .filter(|l| l.starts_with('|'))
+            .map(|l| l.trim_matches('|').split('|').map(|c| c.trim().to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!(display_positions(2), vec![Some(0), Some(1)]);
+        assert_eq!(display_positions(9), (0..9).map(Some).collect::<Vec<_>>());
+        assert_eq!(display_positions(10), vec![Some(0), Some(1), Some(2), None, Some(7), Some(8), Some(9)]);
+        assert_eq!(display_positions(100), vec![Some(0), Some(1), Some(2), None, Some(97), Some(98), Some(99)]);
+    }
+
+    #[test]
+    fn tall_frame() {
+        let header: Vec<String> = vec!["a".to_string(), "b".to_string()];
+        let df: DataFrame = DataFrame::new(vec![Series::from(0..100), Series::from(100..200)], Some(header));
+        let lines: Vec<Vec<String>> = cells(&df);
+
+        // The header, the first and last 3 rows and a row of "..." between them
+        assert_eq!(lines.len(), 1 + 6 + 1);
+        assert_eq!(lines[0], vec!["a", "b"]);
+        assert_eq!(lines[1], vec!["0", "100"]);
+        assert_eq!(lines[4], vec!["...", "..."]);
+        assert_eq!(lines[7], vec!["99", "199"]);
+    }
+
+    #[test]
+    fn wide_frame() {
+        let cols: Vec<Series> = (0..100).map(|i| Series::new(vec![i as f64, -i as f64])).collect();
+        let df: DataFrame = DataFrame::new(cols, None);
+        let lines: Vec<Vec<String>> = cells(&df);
+
+        // The header and both rows, each cut down to the first and last 3 columns
+        assert_eq!(lines.len(), 1 + 2);
+        assert_eq!(lines[0], vec!["0", "1", "2", "...", "97", "98", "99"]);
+        assert_eq!(lines[2], vec!["0", "-1", "-2", "...", "-97", "-98", "-99"]);
+    }
+}
+
+#[cfg(test)]
+mod var_std_tests {
+    use super::*;
+
+    fn users() -> DataFrame {
+        let header: Vec<String> = vec!["UserID".to_string(), "Weight".to_string(), "Age".to_string()];
+        let data: Vec<Series> = vec![
+            Series::new(vec![0.0, 1.0, 2.0]),
+            Series::new(vec![70.0, f64::NAN, 60.0]),
+            Series::new(vec![42.0, 21.0, 8.0])
+        ];
+        DataFrame::new(data, Some(header))
+    }
+
+    #[test]
+    fn var_axis_0_skips_incomplete_columns() {
+        let var: DataFrame = users().var(0);
+        assert_eq!(var.header_row, vec!["UserID", "Age"]);
+        assert_eq!(var.loc_col("UserID").unwrap(), Series::new(vec![1.0]));
+        assert!(var.loc_col("Age").unwrap().approx_eq(&Series::new(vec![883.0 / 3.0]), 1e-9));
+    }
+
+    #[test]
+    fn var_axis_1_uses_complete_rows() {
+        // Only rows 0 and 2 have no missing value
+        let var: DataFrame = users().var(1);
+        assert_eq!(var.cols, vec![Series::new(vec![2.0]), Series::new(vec![50.0]), Series::new(vec![578.0])]);
+    }
+
+    #[test]
+    fn std_is_root_of_var() {
+        let df: DataFrame = users();
+        for axis in [0, 1] {
+            let (std, var) = (df.std(axis), df.var(axis));
+            assert_eq!(std.cols.len(), var.cols.len());
+            for (s, v) in std.cols.iter().zip(&var.cols) {
+                assert!(s.approx_eq(&v.map_values(f64::sqrt), 1e-12));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_dropna() {
+        // What both axes were before they skipped building the intermediate DataFrame
+        let df: DataFrame = users();
+        for axis in [0, 1] {
+            let valid: DataFrame = df.dropna(axis);
+            let expected: Vec<Series> = if axis == 0 { valid.cols.clone() } else { valid.rows.clone() };
+            let expected: Vec<Series> = expected.iter().map(Series::var).collect();
+            assert_eq!(df.var(axis).cols, expected);
+        }
+    }
+
+    #[test]
+    fn no_complete_rows() {
+        let df: DataFrame = DataFrame::new(vec![Series::new(vec![f64::NAN, 1.0]), Series::new(vec![2.0, f64::NAN])], None);
+        assert_eq!(df.var(1).cols.len(), 0);
+        assert_eq!(df.var(0).cols.len(), 0);
+    }
+}
+
+#[cfg(test)]
+mod describe_by_tests {
+    use super::*;
+
+    fn scores() -> DataFrame {
+        let header: Vec<String> = vec!["Group".to_string(), "Score".to_string(), "Age".to_string()];
+        let data: Vec<Series> = vec![
+            Series::new(vec![2.0, 1.0, 2.0, 1.0, f64::NAN]),
+            Series::new(vec![20.0, 10.0, 40.0, 30.0, 99.0]),
+            Series::new(vec![5.0, 7.0, f64::NAN, 9.0, 99.0])
+        ];
+        DataFrame::new(data, Some(header))
+    }
+
+    #[test]
+    fn per_group_means() {
+        let described: DataFrame = scores().describe_by("Group");
+        assert_eq!(described.loc_col("Group").unwrap(), Series::new(vec![1.0, 2.0]));
+        assert_eq!(described.loc_col("Score_mean").unwrap(), Series::new(vec![20.0, 30.0]));
+        assert_eq!(described.loc_col("Age_mean").unwrap(), Series::new(vec![8.0, 5.0]));
+    }
+
+    #[test]
+    fn counts_and_extremes_skip_nan() {
+        let described: DataFrame = scores().describe_by("Group");
+        assert_eq!(described.loc_col("Score_count").unwrap(), Series::new(vec![2.0, 2.0]));
+        assert_eq!(described.loc_col("Age_count").unwrap(), Series::new(vec![2.0, 1.0]));
+        assert_eq!(described.loc_col("Score_min").unwrap(), Series::new(vec![10.0, 20.0]));
+        assert_eq!(described.loc_col("Score_max").unwrap(), Series::new(vec![30.0, 40.0]));
+        assert!(described.loc_col("Score_std").unwrap().approx_eq(&Series::new(vec![200f64.sqrt(); 2]), 1e-12));
+    }
+
+    #[test]
+    fn header_has_a_block_per_column() {
+        let described: DataFrame = scores().describe_by("Group");
+        let expected: Vec<String> = std::iter::once("Group".to_string())
+            .chain(["Score", "Age"].iter().flat_map(|c| Series::SUMMARY_STATS.iter().map(move |s| format!("{}_{}", c, s))))
+            .collect();
+        assert_eq!(described.header_row, expected);
+    }
+}
+
+#[cfg(test)]
+mod read_csv_tests {
+    use super::*;
+
+    #[test]
+    fn quoted_newline_stays_in_its_record() {
+        let df: DataFrame = read_csv("res/quoted_newline.csv");
+        assert_eq!(df.shape(), (3, 3));
+        assert_eq!(df.loc_col("note").unwrap().to_strings(), vec!["first line\nsecond line", "plain", "a, b"]);
+        assert_eq!(df.loc_col("value").unwrap(), Series::new(vec![10.0, 20.0, 30.0]));
+    }
+
+    #[test]
+    fn quoted_newline_with_crlf() {
+        let df: DataFrame = parse_csv("a,b\r\n\"x\r\ny\",1\r\nz,2\r\n", ',', true, None, 0, None, &[]);
+        assert_eq!(df.nrows(), 2);
+        assert_eq!(df.loc_col("a").unwrap().to_strings(), vec!["x\r\ny", "z"]);
+        assert_eq!(df.loc_col("b").unwrap(), Series::new(vec![1.0, 2.0]));
+    }
+
+    #[test]
+    fn split_records_keeps_quoted_lines_together() {
+        assert_eq!(split_records("a\n\"b\nc\"\n\"d\"\n"), vec!["a", "\"b\nc\"", "\"d\""]);
+        assert_eq!(split_records("a\nb"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn doubled_quotes_are_unescaped() {
+        assert_eq!(split_fields("\"a \"\"b\"\" c\",1", ','), vec!["a \"b\" c", "1"]);
+        assert_eq!(split_fields("\"\"\"\",\"\"", ','), vec!["\"", ""]);
+    }
+
+    #[test]
+    fn quotes_inside_unquoted_fields_are_data() {
+        assert_eq!(split_fields("x,ab\"c\"", ','), vec!["x", "ab\"c\""]);
+        assert_eq!(split_fields("\"\"\"q\"\"\",2", ','), vec!["\"q\"", "2"]);
+    }
+
+    #[test]
+    fn quoted_fields_round_trip() {
+        let values = vec!["a \"b\" c", "\"", "x,y", "plain"];
+        let record: Vec<String> = values.iter().map(|v| quote_field(v.to_string())).collect();
+        assert_eq!(split_fields(&record.join(","), ','), values);
+    }
+
+    #[test]
+    fn na_values_become_nan() {
+        let na_values: Vec<String> = vec!["NA".to_string(), "-999".to_string()];
+        let df: DataFrame = read_csv_opts("res/na_values.csv", ',', true, None, 0, None, na_values);
+        assert_eq!(df.loc_col("reading").unwrap().isna(), Series::new(vec![0.0, 1.0, 1.0, 0.0, 1.0]));
+        assert_eq!(df.loc_col("reading").unwrap().fillna(0.0), Series::new(vec![1.5, 0.0, 0.0, 2.0, 0.0]));
+        // A text column keeps its NA marker as an empty string rather than the marker
+        assert_eq!(df.loc_col("site").unwrap().to_strings(), vec!["A", "B", "C", "", "E"]);
+    }
+
+    #[test]
+    fn empty_fields_are_always_nan() {
+        let df: DataFrame = parse_csv("a,b\n1,\n,2\n", ',', true, None, 0, None, &[]);
+        assert_eq!(df.loc_col("a").unwrap().isna(), Series::new(vec![0.0, 1.0]));
+        assert_eq!(df.loc_col("b").unwrap().isna(), Series::new(vec![1.0, 0.0]));
+    }
+
+    #[test]
+    fn na_values_match_text_exactly() {
+        // -999.0 is the same number as -999 but not the same text
+        let na_values: Vec<String> = vec!["-999".to_string()];
+        let df: DataFrame = parse_csv("x\n-999\n-999.0\n", ',', true, None, 0, None, &na_values);
+        let x: Series = df.loc_col("x").unwrap();
+        assert!(!x.is_string());
+        assert!(x.iloc(0).is_nan());
+        assert_eq!(x.iloc(1), -999.0);
+    }
+
+    #[test]
+    fn without_na_values_markers_are_text() {
+        let df: DataFrame = read_csv_opts("res/na_values.csv", ',', true, None, 0, None, vec![]);
+        assert!(df.loc_col("reading").unwrap().is_string());
+    }
+}
+
+#[cfg(test)]
+mod kfold_tests {
+    use super::*;
+
+    fn frame(rows: usize) -> DataFrame {
+        DataFrame::new(vec![Series::from(0..rows as i32)], None)
+    }
+
+    #[test]
+    fn folds_partition_the_rows() {
+        let folds: Vec<Vec<usize>> = frame(10).kfold_indices(3, Some(7));
+        assert_eq!(folds.len(), 3);
+        // Sizes differ by at most one
+        let mut sizes: Vec<usize> = folds.iter().map(Vec::len).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![3, 3, 4]);
+
+        let mut all: Vec<usize> = folds.concat();
+        all.sort();
+        assert_eq!(all, (0..10).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn seeded_folds_are_reproducible() {
+        let df: DataFrame = frame(50);
+        assert_eq!(df.kfold_indices(5, Some(42)), df.kfold_indices(5, Some(42)));
+        assert_ne!(df.kfold_indices(5, Some(42)), df.kfold_indices(5, Some(43)));
+    }
+
+    #[test]
+    fn one_fold_per_row() {
+        let mut folds: Vec<Vec<usize>> = frame(4).kfold_indices(4, None);
+        assert!(folds.iter().all(|f| f.len() == 1));
+        folds.sort();
+        assert_eq!(folds, vec![vec![0], vec![1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Number of folds must be between 1 and the number of rows (3)")]
+    fn rejects_more_folds_than_rows() {
+        frame(3).kfold_indices(4, Some(1));
+    }
+}
+
+#[cfg(test)]
+mod column_stats_tests {
+    use super::*;
+
+    fn users() -> DataFrame {
+        let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+        let data: Vec<Series> = vec![
+            Series::new(vec![0.0, 1.0, 2.0]),
+            Series::new(vec![42.0, f64::NAN, 8.0]),
+            Series::new(vec![183.0, 160.0, 132.0])
+        ];
+        DataFrame::new(data, Some(header))
+    }
+
+    #[test]
+    fn one_row_per_column_and_statistic() {
+        let stats: DataFrame = users().column_stats();
+        assert_eq!(stats.shape(), (3 * Series::SUMMARY_STATS.len(), 3));
+    }
+
+    #[test]
+    fn rows_are_grouped_by_column() {
+        let stats: DataFrame = users().column_stats();
+        let expected: Vec<f64> = (0..3).flat_map(|c| vec![c as f64; Series::SUMMARY_STATS.len()]).collect();
+        assert_eq!(stats.loc_col("column").unwrap(), Series::new(expected));
+    }
+
+    #[test]
+    fn values_match_summary() {
+        let df: DataFrame = users();
+        let stats: DataFrame = df.column_stats();
+        let expected: Vec<f64> = df.cols.iter().flat_map(Series::summary).collect();
+        assert_eq!(stats.loc_col("value").unwrap(), Series::new(expected));
+
+        // Age skips its NaN
+        assert_eq!(stats.loc_col("value").unwrap().iloc(5), 2.0);
+        assert_eq!(stats.loc_col("value").unwrap().iloc(6), 25.0);
+    }
+
+    #[test]
+    fn statistics_cycle_in_order() {
+        let stats: DataFrame = users().column_stats();
+        let expected: Vec<String> = (0..3).flat_map(|_| Series::SUMMARY_STATS.iter().map(|s| s.to_string())).collect();
+        assert!(stats.loc_col("statistic").unwrap().is_string());
+        assert_eq!(stats.loc_col("statistic").unwrap().to_strings(), expected);
+    }
+}
+
+#[cfg(test)]
+mod agg_custom_tests {
+    use super::*;
+
+    fn users() -> DataFrame {
+        let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+        let data: Vec<Series> = vec![
+            Series::new(vec![0.0, 1.0, 2.0]),
+            Series::new(vec![42.0, 21.0, 8.0]),
+            Series::new(vec![183.0, 160.0, 132.0])
+        ];
+        DataFrame::new(data, Some(header))
+    }
+
+    fn min_max(s: &Series) -> Series {
+        Series::new(vec![s.min().iloc(0), s.max().iloc(0)])
+    }
+
+    #[test]
+    fn min_max_per_column() {
+        let ranges: DataFrame = users().agg_custom(min_max, 0);
+        assert_eq!(ranges.shape(), (2, 3));
+        assert_eq!(ranges.loc_col("UserID").unwrap(), Series::new(vec![0.0, 2.0]));
+        assert_eq!(ranges.loc_col("Age").unwrap(), Series::new(vec![8.0, 42.0]));
+        assert_eq!(ranges.loc_col("Height").unwrap(), Series::new(vec![132.0, 183.0]));
+    }
+
+    #[test]
+    fn min_max_per_row() {
+        let ranges: DataFrame = users().agg_custom(min_max, 1);
+        assert_eq!(ranges.shape(), (2, 3));
+        assert_eq!(ranges.icol(0), Series::new(vec![0.0, 183.0]));
+        assert_eq!(ranges.icol(2), Series::new(vec![2.0, 132.0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Aggregation results must have same dimensions")]
+    fn rejects_ragged_results() {
+        users().agg_custom(|s| Series::new(s.iter().copied().filter(|&x| x < 100.0).collect()), 0);
+    }
+}
+
+#[cfg(test)]
+mod fillna_tests {
+    use super::*;
+
+    #[test]
+    fn fills_every_column_and_keeps_rows_aligned() {
+        let header: Vec<String> = vec!["Age".to_string(), "Height".to_string()];
+        let data: Vec<Series> = vec![Series::new(vec![42.0, f64::NAN, 8.0]), Series::new(vec![f64::NAN, 160.0, f64::NAN])];
+        let filled: DataFrame = DataFrame::new(data, Some(header)).fillna(-1.0);
+
+        assert_eq!(filled.loc_col("Age").unwrap(), Series::new(vec![42.0, -1.0, 8.0]));
+        assert_eq!(filled.loc_col("Height").unwrap(), Series::new(vec![-1.0, 160.0, -1.0]));
+        assert_eq!(filled.irow(1), Series::new(vec![-1.0, 160.0]));
+    }
+}
+
+#[cfg(test)]
+mod quality_report_tests {
+    use super::*;
+
+    fn report() -> DataFrame {
+        let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string(), "Empty".to_string()];
+        let data: Vec<Series> = vec![
+            Series::new(vec![0.0, 1.0, 2.0, 3.0]),
+            Series::new(vec![42.0, 42.0, f64::NAN, 42.0]),
+            Series::new(vec![f64::NAN, 160.0, f64::NAN, 150.0]),
+            Series::new(vec![f64::NAN; 4])
+        ];
+        DataFrame::new(data, Some(header)).quality_report()
+    }
+
+    #[test]
+    fn null_fraction_and_distinct() {
+        let report: DataFrame = report();
+        assert_eq!(report.loc_col("column").unwrap(), Series::new(vec![0.0, 1.0, 2.0, 3.0]));
+        assert_eq!(report.loc_col("non_null").unwrap(), Series::new(vec![4.0, 3.0, 2.0, 0.0]));
+        assert_eq!(report.loc_col("null_fraction").unwrap(), Series::new(vec![0.0, 0.25, 0.5, 1.0]));
+        assert_eq!(report.loc_col("distinct").unwrap(), Series::new(vec![4.0, 1.0, 2.0, 0.0]));
+    }
+
+    #[test]
+    fn constant_columns_ignore_nan() {
+        assert_eq!(report().loc_col("is_constant").unwrap(), Series::new(vec![0.0, 1.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn min_max_mean_skip_nan() {
+        let report: DataFrame = report();
+        assert_eq!(report.irow(2).slice(4, 7), Series::new(vec![150.0, 160.0, 155.0]));
+        assert!(report.irow(3).slice(4, 7).iter().all(|x| x.is_nan()));
+    }
+}
+
+#[cfg(test)]
+mod size_tests {
+    use super::*;
+
+    // The method, the field and the shape must all agree on the number of cells
+    fn check(df: &DataFrame, rows: usize, cols: usize) {
+        assert_eq!(df.shape(), (rows, cols));
+        assert_eq!(df.nrows(), rows);
+        assert_eq!(df.ncols(), cols);
+        assert_eq!(df.size(), rows * cols);
+        assert_eq!(df.size, rows * cols);
+    }
+
+    #[test]
+    fn counts_cells_not_columns() {
+        let df: DataFrame = DataFrame::new(vec![Series::new(vec![1.0, 2.0, 3.0, 4.0]), Series::new(vec![5.0, 6.0, 7.0, 8.0])], None);
+        check(&df, 4, 2);
+    }
+
+    #[test]
+    fn every_constructor_agrees() {
+        check(&DataFrame::from(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]), 2, 3);
+        check(&DataFrame::from_rows(vec![Series::new(vec![1.0, 2.0])], vec!["a".to_string(), "b".to_string()]), 1, 2);
+        check(&DataFrame::new(vec![], None), 0, 0);
+    }
+
+    #[test]
+    fn updated_by_reshaping() {
+        let df: DataFrame = DataFrame::new(vec![Series::new(vec![1.0, 2.0]), Series::new(vec![3.0, 4.0])], None);
+        check(&df.append_row(Series::new(vec![5.0, 6.0])), 3, 2);
+        check(&df.insert_col(2, "x", Series::new(vec![0.0, 0.0])), 2, 3);
+        check(&df.take_rows(&[1]), 1, 2);
+    }
+}
+
+#[cfg(test)]
+mod to_json_tests {
+    use super::*;
+
+    fn users() -> DataFrame {
+        let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string()];
+        let data: Vec<Series> = vec![Series::new(vec![0.0, 1.0]), Series::new(vec![42.5, f64::NAN])];
+        DataFrame::new(data, Some(header))
+    }
+
+    #[test]
+    fn records() {
+        assert_eq!(users().to_json("records"), r#"[{"UserID": 0.0, "Age": 42.5}, {"UserID": 1.0, "Age": null}]"#);
+    }
+
+    #[test]
+    fn columns() {
+        assert_eq!(users().to_json("columns"), r#"{"UserID": [0.0, 1.0], "Age": [42.5, null]}"#);
+    }
+
+    #[test]
+    fn missing_and_infinite_values_are_null() {
+        let df: DataFrame = DataFrame::new(vec![Series::new(vec![f64::NAN, f64::INFINITY, -f64::INFINITY])], Some(vec!["x".to_string()]));
+        assert_eq!(df.to_json("columns"), r#"{"x": [null, null, null]}"#);
+        assert_eq!(df.to_json("records"), r#"[{"x": null}, {"x": null}, {"x": null}]"#);
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        let names: Series = Series::from_strings(vec!["say \"hi\"".to_string(), "a\\b\nc".to_string()]);
+        let df: DataFrame = DataFrame::new(vec![names], Some(vec!["name \"quoted\"".to_string()]));
+        let parsed = json::parse(&df.to_json("records"));
+        let expected = json::Value::Array(vec![
+            json::Value::Object(vec![("name \"quoted\"".to_string(), json::Value::String("say \"hi\"".to_string()))]),
+            json::Value::Object(vec![("name \"quoted\"".to_string(), json::Value::String("a\\b\nc".to_string()))])
+        ]);
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn empty_frame() {
+        let df: DataFrame = DataFrame::new(vec![], None);
+        assert_eq!(df.to_json("records"), "[]");
+        assert_eq!(df.to_json("columns"), "{}");
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown orient index")]
+    fn rejects_unknown_orient() {
+        users().to_json("index");
+    }
+}
+
+#[cfg(test)]
+mod to_csv_tests {
+    use super::*;
+
+    // Writes the DataFrame to a file of its own and reads the text back
+    fn written(df: &DataFrame, name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("rusty_pandas_{}.csv", name));
+        df.to_csv(path.to_str().unwrap());
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        text
+    }
+
+    #[test]
+    fn doubles_embedded_quotes() {
+        let notes: Series = Series::from_strings(vec!["say \"hi\", ok".to_string(), "plain".to_string()]);
+        let df: DataFrame = DataFrame::new(vec![notes], Some(vec!["note".to_string()]));
+        assert_eq!(written(&df, "quotes"), "note\n\"say \"\"hi\"\", ok\"\nplain");
+    }
+
+    #[test]
+    fn quotes_header_names() {
+        let df: DataFrame = DataFrame::new(
+            vec![Series::new(vec![1.0]), Series::new(vec![2.0])],
+            Some(vec!["a, b".to_string(), "the \"c\"".to_string()])
+        );
+        assert_eq!(written(&df, "header"), "\"a, b\",\"the \"\"c\"\"\"\n1,2");
+    }
+
+    #[test]
+    fn quotes_line_breaks() {
+        let notes: Series = Series::from_strings(vec!["two\nlines".to_string(), "cr\rhere".to_string()]);
+        let df: DataFrame = DataFrame::new(vec![notes], Some(vec!["note".to_string()]));
+        assert_eq!(written(&df, "breaks"), "note\n\"two\nlines\"\n\"cr\rhere\"");
+    }
+}
+
+
+#[cfg(test)]
+mod describe_tests {
+    use super::*;
+
+    #[test]
+    fn statistics_are_labelled() {
+        let header: Vec<String> = vec!["Age".to_string(), "Height".to_string()];
+        let df: DataFrame = DataFrame::new(vec![Series::new(vec![42.0, 21.0, 8.0]), Series::new(vec![183.0, 160.0, f64::NAN])], Some(header));
+        let described: DataFrame = df.describe();
+        assert_eq!(described.shape(), (Series::DESCRIBE_STATS.len(), 3));
+        assert!(described.loc_col("statistic").unwrap().is_string());
+        assert_eq!(described.loc_col("statistic").unwrap().to_strings(), Series::DESCRIBE_STATS.to_vec());
+        assert_eq!(described.loc_col("Height").unwrap().iloc(0), 2.0);
+        assert_eq!(described.loc_col("Age").unwrap().iloc(7), 42.0);
+    }
+}
+
+#[cfg(test)]
+mod read_csv_typed_tests {
+    use super::*;
+
+    fn read_typed(text: &str, name: &str, types: HashMap<String, &str>) -> DataFrame {
+        let path = std::env::temp_dir().join(format!("rusty_pandas_{}.csv", name));
+        fs::write(&path, text).unwrap();
+        let df = read_csv_typed(path.to_str().unwrap(), types);
+        fs::remove_file(&path).unwrap();
+        df
+    }
+
+    #[test]
+    fn i64_keeps_whole_numbers_written_as_floats() {
+        let types: HashMap<String, &str> = HashMap::from([("a".to_string(), "i64")]);
+        let df: DataFrame = read_typed("a\n1.0\n3\n-2e1\n2.5\nx\n", "whole_floats", types);
+        let a: Series = df.loc_col("a").unwrap();
+        assert_eq!(a.iloc(0), 1.0);
+        assert_eq!(a.iloc(1), 3.0);
+        assert_eq!(a.iloc(2), -20.0);
+        assert!(a.iloc(3).is_nan());
+        assert!(a.iloc(4).is_nan());
+    }
+
+    #[test]
+    fn i64_drops_infinities() {
+        let types: HashMap<String, &str> = HashMap::from([("a".to_string(), "i64")]);
+        let df: DataFrame = read_typed("a\ninf\n4\n", "infinities", types);
+        assert!(df.loc_col("a").unwrap().iloc(0).is_nan());
+        assert_eq!(df.loc_col("a").unwrap().iloc(1), 4.0);
+    }
+}
+
+#[cfg(test)]
+mod read_csv_chunked_tests {
+    use super::*;
+
+    fn chunks(filename: &str, chunk_rows: usize, opts: &CsvOptions) -> Vec<DataFrame> {
+        let mut chunks = vec![];
+        read_csv_chunked(filename, chunk_rows, opts, |chunk| chunks.push(chunk));
+        chunks
+    }
+
+    fn whole(filename: &str, opts: &CsvOptions) -> DataFrame {
+        let opts = opts.clone();
+        read_csv_opts(filename, opts.delimiter, opts.has_header, opts.thousands, opts.skip_rows, opts.comment, opts.na_values)
+    }
+
+    #[test]
+    fn one_chunk_matches_read_csv_opts() {
+        let cases = [
+            ("res/thousands.csv", CsvOptions { thousands: Some(','), ..CsvOptions::default() }),
+            ("res/no_header.tsv", CsvOptions { delimiter: '\t', has_header: false, ..CsvOptions::default() }),
+            ("res/banner.csv", CsvOptions { skip_rows: 2, comment: Some('#'), ..CsvOptions::default() }),
+            ("res/quoted_newline.csv", CsvOptions::default())
+        ];
+        for (filename, opts) in cases {
+            assert_eq!(chunks(filename, 100, &opts), vec![whole(filename, &opts)], "{}", filename);
+        }
+    }
+
+    #[test]
+    fn na_values_are_missing_in_every_chunk() {
+        let opts = CsvOptions { na_values: vec!["NA".to_string(), "-999".to_string()], ..CsvOptions::default() };
+        let readings: Vec<f64> = chunks("res/na_values.csv", 2, &opts).iter()
+            .flat_map(|chunk| chunk.loc_col("reading").unwrap().fillna(0.0).to_vec())
+            .collect();
+        assert_eq!(readings, vec![1.5, 0.0, 0.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn headerless_first_record_is_data() {
+        let opts = CsvOptions { delimiter: '\t', has_header: false, ..CsvOptions::default() };
+        let chunks: Vec<DataFrame> = chunks("res/no_header.tsv", 1, &opts);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].header_row, vec!["0", "1", "2"]);
+        assert_eq!(chunks[0].irow(0), Series::new(vec![1.0, 2.0, 3.0]));
+        assert_eq!(chunks[1].irow(0), Series::new(vec![4.0, 5.0, 6.0]));
+    }
+}
+
+#[cfg(test)]
+mod concat_tests {
+    use super::*;
+
+    fn frame(header: &[&str], cols: Vec<Vec<f64>>) -> DataFrame {
+        DataFrame::new(cols.into_iter().map(Series::new).collect(), Some(header.iter().map(|h| h.to_string()).collect()))
+    }
+
+    #[test]
+    fn axis_0_stacks_rows() {
+        let a: DataFrame = frame(&["UserID", "Age"], vec![vec![0.0], vec![42.0]]);
+        let b: DataFrame = frame(&["UserID", "Age"], vec![vec![1.0, 2.0], vec![21.0, 8.0]]);
+        let stacked: DataFrame = concat(vec![a, b], 0);
+        assert_eq!(stacked, frame(&["UserID", "Age"], vec![vec![0.0, 1.0, 2.0], vec![42.0, 21.0, 8.0]]));
+        assert_eq!(stacked.nrows(), 3);
+    }
+
+    #[test]
+    fn axis_1_places_columns_side_by_side() {
+        let a: DataFrame = frame(&["UserID"], vec![vec![0.0, 1.0]]);
+        let b: DataFrame = frame(&["Age", "Height"], vec![vec![42.0, 21.0], vec![183.0, 160.0]]);
+        let joined: DataFrame = concat(vec![a, b], 1);
+        assert_eq!(joined, frame(&["UserID", "Age", "Height"], vec![vec![0.0, 1.0], vec![42.0, 21.0], vec![183.0, 160.0]]));
+        assert_eq!(joined.irow(1), Series::new(vec![1.0, 21.0, 160.0]));
+    }
+
+    #[test]
+    fn no_frames_is_empty() {
+        assert_eq!(concat(vec![], 0).shape(), (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't match")]
+    fn axis_0_header_mismatch_panics() {
+        let a: DataFrame = frame(&["UserID", "Age"], vec![vec![0.0], vec![42.0]]);
+        let b: DataFrame = frame(&["UserID", "Height"], vec![vec![1.0], vec![160.0]]);
+        concat(vec![a, b], 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "has 1 rows but expected 2")]
+    fn axis_1_row_mismatch_panics() {
+        let a: DataFrame = frame(&["UserID"], vec![vec![0.0, 1.0]]);
+        let b: DataFrame = frame(&["Age"], vec![vec![42.0]]);
+        concat(vec![a, b], 1);
+    }
+}
+
+#[cfg(test)]
+mod insert_col_tests {
+    use super::*;
+
+    fn users() -> DataFrame {
+        let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+        let data: Vec<Series> = vec![
+            Series::new(vec![0.0, 1.0, 2.0]),
+            Series::new(vec![42.0, 21.0, 8.0]),
+            Series::new(vec![183.0, 160.0, 132.0])
+        ];
+        DataFrame::new(data, Some(header))
+    }
+
+    #[test]
+    fn at_the_front() {
+        let df: DataFrame = users().insert_col(0, "Weight", Series::new(vec![100.0, 300.0, 77.0]));
+        assert_eq!(df.header_row, vec!["Weight", "UserID", "Age", "Height"]);
+        assert_eq!(df.icol(0), Series::new(vec![100.0, 300.0, 77.0]));
+        assert_eq!(df.irow(1), Series::new(vec![300.0, 1.0, 21.0, 160.0]));
+    }
+
+    #[test]
+    fn at_the_back() {
+        let df: DataFrame = users().insert_col(3, "Weight", Series::new(vec![100.0, 300.0, 77.0]));
+        assert_eq!(df.header_row, vec!["UserID", "Age", "Height", "Weight"]);
+        assert_eq!(df.icol(3), Series::new(vec![100.0, 300.0, 77.0]));
+        assert_eq!(df.irow(2), Series::new(vec![2.0, 8.0, 132.0, 77.0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid position 4 to insert into a DataFrame with 3 columns")]
+    fn past_the_back_panics() {
+        users().insert_col(4, "Weight", Series::new(vec![100.0, 300.0, 77.0]));
+    }
+}
+
+#[cfg(test)]
+mod add_series_tests {
+    use super::*;
+
+    fn users() -> DataFrame {
+        let header: Vec<String> = vec!["UserID".to_string(), "Age".to_string(), "Height".to_string()];
+        let data: Vec<Series> = vec![
+            Series::new(vec![0.0, 1.0]),
+            Series::new(vec![42.0, 21.0]),
+            Series::new(vec![183.0, 160.0])
+        ];
+        DataFrame::new(data, Some(header))
+    }
+
+    #[test]
+    fn axis_0_adds_to_every_row() {
+        let biased: DataFrame = users().add_series(Series::new(vec![1.0, 0.0, -100.0]), 0);
+        assert_eq!(biased.irow(0), Series::new(vec![1.0, 42.0, 83.0]));
+        assert_eq!(biased.irow(1), Series::new(vec![2.0, 21.0, 60.0]));
+        assert_eq!(biased.header_row, users().header_row);
+    }
+
+    #[test]
+    fn axis_1_adds_to_every_column() {
+        let biased: DataFrame = users().add_series(Series::new(vec![10.0, 20.0]), 1);
+        assert_eq!(biased.loc_col("UserID").unwrap(), Series::new(vec![10.0, 21.0]));
+        assert_eq!(biased.loc_col("Height").unwrap(), Series::new(vec![193.0, 180.0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Series must have same dimensions")]
+    fn axis_0_length_mismatch_panics() {
+        users().add_series(Series::new(vec![1.0, 2.0]), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Series must have same dimensions")]
+    fn axis_1_length_mismatch_panics() {
+        users().add_series(Series::new(vec![1.0, 2.0, 3.0]), 1);
+    }
+}
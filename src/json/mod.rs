@@ -0,0 +1,215 @@
+// Just enough JSON to move numbers in and out without pulling in serde_json
+
+/// Formats a number as a JSON value. JSON has no NaN or infinity, so those become null
+pub(crate) fn number(x: f64) -> String {
+    if x.is_finite() { format!("{:?}", x) } else { "null".to_string() }
+}
+
+/// Formats numbers as a JSON array, e.g. `[1.0, 2.0, null]`
+pub(crate) fn number_array(xs: &[f64]) -> String {
+    format!("[{}]", xs.iter().map(|&x| number(x)).collect::<Vec<String>>().join(", "))
+}
+
+/// Formats text as a JSON string, escaping whatever JSON requires
+pub(crate) fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c)
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Formats already formatted keys and values as a JSON object, e.g. `{"a": 1.0, "b": null}`
+pub(crate) fn object(entries: &[(String, String)]) -> String {
+    let fields: Vec<String> = entries.iter().map(|(k, v)| format!("{}: {}", string(k), v)).collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+/// Formats already formatted values as a JSON array
+pub(crate) fn array(values: &[String]) -> String {
+    format!("[{}]", values.join(", "))
+}
+
+/// Parses a JSON array of numbers and nulls, turning the nulls into NaN
+pub(crate) fn parse_number_array(s: &str) -> Result<Vec<f64>, String> {
+    match try_parse(s)? {
+        Value::Array(values) => values.into_iter()
+            .map(|v| match v {
+                Value::Null => Ok(f64::NAN),
+                Value::Number(x) => Ok(x),
+                v => Err(format!("Expected a JSON number but got {:?}", v))
+            })
+            .collect(),
+        v => Err(format!("Expected a JSON array but got {:?}", v))
+    }
+}
+
+/// A parsed JSON value. Objects keep their keys in the order they were written
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>)
+}
+
+/// Parses a JSON document, panicking if it is malformed
+pub(crate) fn parse(s: &str) -> Value {
+    try_parse(s).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Parses a JSON document, describing what is wrong with it if it is malformed
+pub(crate) fn try_parse(s: &str) -> Result<Value, String> {
+    let mut parser = Parser { chars: s.chars().collect(), pos: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos != parser.chars.len() { return Err(format!("Unexpected trailing JSON at position {}", parser.pos)) }
+    Ok(value)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize
+}
+
+impl Parser {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() { self.pos += 1; }
+    }
+
+    fn peek(&mut self) -> Result<char, String> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied().ok_or_else(|| "Unexpected end of JSON".to_string())
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.peek()? != c { return Err(format!("Expected '{}' at position {} of JSON", c, self.pos)) }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &str, value: Value) -> Result<Value, String> {
+        let end = self.pos + word.chars().count();
+        if end > self.chars.len() || self.chars[self.pos..end].iter().collect::<String>() != word {
+            return Err(format!("Unexpected JSON at position {}", self.pos))
+        }
+        self.pos = end;
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        match self.peek()? {
+            'n' => self.literal("null", Value::Null),
+            't' => self.literal("true", Value::Bool(true)),
+            'f' => self.literal("false", Value::Bool(false)),
+            '"' => Ok(Value::String(self.string()?)),
+            '[' => {
+                self.pos += 1;
+                let mut values = vec![];
+                if self.peek()? == ']' { self.pos += 1; return Ok(Value::Array(values)) }
+                loop {
+                    values.push(self.value()?);
+                    if self.peek()? == ',' { self.pos += 1; } else { break }
+                }
+                self.expect(']')?;
+                Ok(Value::Array(values))
+            },
+            '{' => {
+                self.pos += 1;
+                let mut entries = vec![];
+                if self.peek()? == '}' { self.pos += 1; return Ok(Value::Object(entries)) }
+                loop {
+                    if self.peek()? != '"' { return Err(format!("Expected a JSON key at position {}", self.pos)) }
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    entries.push((key, self.value()?));
+                    if self.peek()? == ',' { self.pos += 1; } else { break }
+                }
+                self.expect('}')?;
+                Ok(Value::Object(entries))
+            },
+            _ => {
+                let start = self.pos;
+                while self.pos < self.chars.len() && "+-.eE0123456789".contains(self.chars[self.pos]) { self.pos += 1; }
+                let num: String = self.chars[start..self.pos].iter().collect();
+                num.parse::<f64>().map(Value::Number).map_err(|_| format!("Expected a JSON number but got {}", num))
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let hex: String = self.chars.get(self.pos..self.pos + 4).ok_or_else(|| "Unexpected end of JSON".to_string())?.iter().collect();
+        self.pos += 4;
+        u32::from_str_radix(&hex, 16).map_err(|_| format!("Invalid JSON escape \\u{}", hex))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            let c = *self.chars.get(self.pos).ok_or_else(|| "Unterminated JSON string".to_string())?;
+            self.pos += 1;
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escaped = *self.chars.get(self.pos).ok_or_else(|| "Unterminated JSON string".to_string())?;
+                    self.pos += 1;
+                    match escaped {
+                        'n' => out.push('\n'),
+                        'r' => out.push('\r'),
+                        't' => out.push('\t'),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'u' => {
+                            let mut code = self.hex4()?;
+                            // Characters outside the BMP are written as a surrogate pair
+                            if (0xD800..0xDC00).contains(&code) && self.chars.get(self.pos..self.pos + 2) == Some(&['\\', 'u']) {
+                                self.pos += 2;
+                                code = 0x10000 + ((code - 0xD800) << 10) + (self.hex4()? - 0xDC00);
+                            }
+                            out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                        },
+                        c => out.push(c)
+                    }
+                },
+                c => out.push(c)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod parse_number_array_tests {
+    use super::*;
+
+    #[test]
+    fn numbers_and_nulls() {
+        let parsed = parse_number_array(" [1.0, 2, null, -4.5e1] ").unwrap();
+        assert_eq!(parsed[..2], [1.0, 2.0]);
+        assert!(parsed[2].is_nan());
+        assert_eq!(parsed[3], -45.0);
+        assert_eq!(parse_number_array("[]").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn malformed_json_is_an_error() {
+        assert!(parse_number_array("[1,2,]").is_err());
+        assert!(parse_number_array("[1,\"a\"]").is_err());
+        assert!(parse_number_array("[1, [2]]").is_err());
+        assert!(parse_number_array("{\"a\": 1}").is_err());
+        assert!(parse_number_array("[1, 2").is_err());
+        assert!(parse_number_array("").is_err());
+    }
+}
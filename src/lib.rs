@@ -1,6 +1,7 @@
 use pyo3::prelude::*;
 pub mod series;
 pub mod dataframe;
+mod json;
 
 pub use series::Series;
 pub use dataframe::DataFrame;
@@ -10,8 +11,13 @@ fn rusty_pandas(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<Series>()?;
     m.add_class::<DataFrame>()?;
     m.add_function(wrap_pyfunction!(dataframe::read_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(dataframe::read_csv_opts, m)?)?;
+    m.add_function(wrap_pyfunction!(dataframe::try_read_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(dataframe::read_csv_typed, m)?)?;
+    m.add_function(wrap_pyfunction!(dataframe::read_json, m)?)?;
     m.add_function(wrap_pyfunction!(dataframe::read_csv_from_folder, m)?)?;
     m.add_function(wrap_pyfunction!(dataframe::read_csv_by_glob, m)?)?;
     m.add_function(wrap_pyfunction!(dataframe::from_hashmap, m)?)?;
+    m.add_function(wrap_pyfunction!(dataframe::concat, m)?)?;
     Ok(())
 }
@@ -4,17 +4,27 @@ use rayon::prelude::*;
 use num_traits::*;
 use std::ops::*;
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::HashMap;
+use crate::dataframe::DataFrame;
+use crate::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Below this many values methods run sequentially, since splitting the work up across threads
+// costs more than it saves (see `Series::set_par_threshold`)
+static PAR_THRESHOLD: AtomicUsize = AtomicUsize::new(8192);
 
 #[derive(Debug, Clone)]
 #[pyclass]
 pub struct Series {
-    data: Vec<f64> 
+    data: Vec<f64>,
+    // The original text of a string column. `data` still holds whatever parsed as a number so
+    // the numeric methods keep working on it
+    strings: Option<Vec<String>>
 }
 
 #[pymethods]
 impl Series {
-    const LOWER_PAR_BOUND: usize = 8192;
-
     /// Creates a new Series
     ///
     /// # Example
@@ -24,7 +34,37 @@ impl Series {
     /// ```
     #[new]
     pub fn new(data: Vec<f64>) -> Series {
-        Series { data }
+        Series { data, strings: None }
+    }
+
+    /// Sets how many values a Series (or how many columns or rows a DataFrame) needs before
+    /// methods split their work across threads. The default is 8192
+    ///
+    /// The best value depends on the machine: raise it if small inputs are slowed down by the
+    /// threading overhead, or lower it to parallelize smaller inputs on many cores. It applies to
+    /// every Series and DataFrame in the program
+    ///
+    /// # Example
+    /// ```
+    /// Series::set_par_threshold(100_000);
+    /// assert_eq!(Series::par_threshold(), 100_000);
+    /// Series::set_par_threshold(8192);
+    /// ```
+    #[staticmethod]
+    pub fn set_par_threshold(n: usize) {
+        PAR_THRESHOLD.store(n, Ordering::Relaxed);
+    }
+
+    /// Returns how many values are needed before methods run in parallel (see
+    /// `set_par_threshold`)
+    ///
+    /// # Example
+    /// ```
+    /// assert_eq!(Series::par_threshold(), 8192);
+    /// ```
+    #[staticmethod]
+    pub fn par_threshold() -> usize {
+        PAR_THRESHOLD.load(Ordering::Relaxed)
     }
 
     /// Returns the number of elements in the Series
@@ -65,19 +105,23 @@ impl Series {
 
     /// Sums the values inside the Series
     ///
+    /// The running total carries a compensation term (Neumaier's variant of Kahan summation), so
+    /// small values aren't lost when added to much larger ones
+    ///
     /// # Example
     /// ```
     /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0];
     /// let series: Series = Series::new(data);
     /// assert_eq!(data.sum(), 15.0);
+    ///
+    /// // Naively each 1.0 would vanish into the 1e16, leaving 0.0
+    /// let mut data: Vec<f64> = vec![1e16];
+    /// data.extend(vec![1.0; 100_000]);
+    /// data.push(-1e16);
+    /// assert_eq!(Series::new(data).sum(), Series::new(vec![100_000.0]));
     /// ```
     pub fn sum(&self) -> Series {
-        if self.size() < Series::LOWER_PAR_BOUND {
-            Series::new(vec![(&self.dropna().data).iter().sum()])
-        }
-        else {
-            Series::new(vec![(&self.dropna().data).par_iter().sum()])
-        }
+        Series::new(vec![Series::stable_sum(&self.dropna().data)])
     }
 
     /// Computes the product of all values inside the Series
@@ -89,7 +133,7 @@ impl Series {
     /// assert_eq!(data.sum(), 120.0);
     /// ```
     pub fn prod(&self) -> Series {
-        if self.size() < Series::LOWER_PAR_BOUND {
+        if self.size() < Series::par_threshold() {
             Series::new(vec![(&self.dropna().data).iter().product()])
         }
         else {
@@ -110,7 +154,7 @@ impl Series {
     /// assert_eq!(data.dropna(), expected);
     /// ```
     pub fn dropna(&self) -> Series {
-        if self.size() < Series::LOWER_PAR_BOUND {
+        if self.size() < Series::par_threshold() {
             Series::new(self.data.clone().into_iter().filter(|x| !x.is_nan()).collect())
         }
         else {
@@ -131,7 +175,7 @@ impl Series {
     /// assert_eq!(data.isna(), expected);
     /// ```
     pub fn isna(&self) -> Series {
-        if self.size() < Series::LOWER_PAR_BOUND {
+        if self.size() < Series::par_threshold() {
             Series::new(self.data.clone().into_iter().map(|x| x.is_nan() as i32 as f64).collect())
         }
         else {
@@ -152,7 +196,7 @@ impl Series {
     /// assert_eq!(data.notna(), expected);
     /// ```
     pub fn notna(&self) -> Series {
-        if self.size() < Series::LOWER_PAR_BOUND {
+        if self.size() < Series::par_threshold() {
             Series::new(self.data.clone().into_iter().map(|x| !x.is_nan() as i32 as f64).collect())
         }
         else {
@@ -160,566 +204,2110 @@ impl Series {
         }
     }
 
-    /*
-    /// Indicates whether or not the Series contains any elements that satisfy a predicate
+    /// Counts the existing (non-missing) values, unlike `size` which counts every element
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 3.0, 7.0, 14.0, 19.0];
+    /// use std::f64::NAN;
+    /// let data: Vec<f64> = vec![1.0, 2.0, NAN, 4.0, NAN];
     ///
     /// let series: Series = Series::new(data);
-    /// let is_even = |x: f64| -> { x % 2 == 0 };
-    /// assert!(data.any(is_even));
+    /// assert_eq!(series.count(), 3usize);
     /// ```
-    pub fn any(&self, pred: &dyn Fn(f64) -> bool) -> bool {
-        self.data.clone().into_par_iter().any(pred)
+    pub fn count(&self) -> usize {
+        if self.size() < Series::par_threshold() {
+            self.data.iter().filter(|x| !x.is_nan()).count()
+        }
+        else {
+            self.data.par_iter().filter(|x| !x.is_nan()).count()
+        }
     }
-    */
 
-    /// Sorts the series
+    /// Indicates values that are in `values`. NaN only counts as being in `values` if `values`
+    /// contains a NaN too
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![3.0, 1.0, 4.0, 1.0, 5.0, 9.0];
+    /// use std::f64::NAN;
+    /// let data: Vec<f64> = vec![1.0, 2.0, NAN, 4.0, 2.0];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![1.0, 1.0, 3.0, 4.0, 5.0, 9.0]);
-    /// assert_eq!(series.sort(), expected);
+    /// assert_eq!(series.isin(vec![2.0, 4.0]), Series::new(vec![0.0, 1.0, 0.0, 1.0, 1.0]));
+    /// assert_eq!(series.isin(vec![NAN, 1.0]), Series::new(vec![1.0, 0.0, 1.0, 0.0, 0.0]));
     /// ```
-    pub fn sort(&self) -> Series {
-        let mut sorted: Vec<f64> = self.dropna().data;
+    pub fn isin(&self, values: Vec<f64>) -> Series {
+        let has_nan = values.iter().any(|x| x.is_nan());
+        let mut sorted: Vec<f64> = values.into_iter().filter(|x| !x.is_nan()).collect();
         sorted.par_sort_by(|a, b| a.partial_cmp(b).unwrap());
-        Series::new(sorted)
+
+        self.indicator(|x| {
+            if x.is_nan() { has_nan }
+            else { sorted.binary_search_by(|y| y.partial_cmp(&x).unwrap()).is_ok() }
+        })
     }
 
-    /// Calculates the mean of the values inside the Series
+    /// Indicates values that lie between `low` and `high`, including both ends if `inclusive`
+    /// and excluding them otherwise. NaN is never between anything
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 3.0, 7.0, 14.0, 19.0];
+    /// use std::f64::NAN;
+    /// let data: Vec<f64> = vec![1.0, 2.0, NAN, 4.0, 5.0];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![8.8]);
-    /// assert_eq!(series.mean(), expected);
+    /// assert_eq!(series.between(2.0, 4.0, true), Series::new(vec![0.0, 1.0, 0.0, 1.0, 0.0]));
+    /// assert_eq!(series.between(2.0, 4.0, false), Series::new(vec![0.0, 0.0, 0.0, 0.0, 0.0]));
     /// ```
-    pub fn mean(&self) -> Series {
-        if self.is_empty() { return Series::zero() }
-        Series::new(vec![self.sum().iloc(0) / self.size() as f64])
+    pub fn between(&self, low: f64, high: f64, inclusive: bool) -> Series {
+        if inclusive { self.indicator(|x| low <= x && x <= high) }
+        else { self.indicator(|x| low < x && x < high) }
     }
 
-    /// Calculates the median of the values inside the Series
+    /// Indicates values greater than `n`. Comparisons with NaN are always false, so NaN gives 0.0
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 3.0, 7.0, 14.0, 19.0];
-    ///
-    /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![7.0]);
-    /// assert_eq!(series.median(), expected);
+    /// use std::f64::NAN;
+    /// let series: Series = Series::new(vec![1.0, 2.0, NAN, 4.0, 5.0]);
+    /// assert_eq!(series.gt(2.0), Series::new(vec![0.0, 0.0, 0.0, 1.0, 1.0]));
     /// ```
-    pub fn median(&self) -> Series {
-        let valid = self.dropna();
-        if valid.is_empty() { return Series::zero() }
-        if valid.size() == 1 { return Series::new(self.data.clone()) }
-
-        let sorted = valid.sort();
-        if valid.size() % 2 == 1 {
-            let median = sorted.iloc(valid.size() / 2 as usize);
-            Series::new(vec![median])
-        }
-        else {
-            let median = (sorted.iloc(valid.size() / 2 - 1 as usize) + sorted.iloc(valid.size() / 2 as usize)) * 0.5;
-            Series::new(vec![median])
-        }
+    pub fn gt(&self, n: f64) -> Series {
+        self.indicator(|x| x > n)
     }
 
-    /// Calculates the mode of values inside the Series
+    /// Indicates values less than `n`
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 3.0, 3.0, 7.0, 14.0, 14.0 19.0, 19.0, 19.0];
-    ///
-    /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![19.0]);
-    /// assert_eq!(series.mode(), expected);
+    /// use std::f64::NAN;
+    /// let series: Series = Series::new(vec![1.0, 2.0, NAN, 4.0, 5.0]);
+    /// assert_eq!(series.lt(2.0), Series::new(vec![1.0, 0.0, 0.0, 0.0, 0.0]));
     /// ```
-    pub fn mode(&self) -> Series {
-        let valid = self.dropna();
-        if valid.is_empty() { return Series::zero() }
-        if valid.size() == 1 { return Series::new(self.data.clone()) }
-
-        // We don't have groupBy identity so going to have to go a bit gonzo
-        // Can't do HashMap/HashSet cause floats can't be hashed T_T
-        let mut indices = vec![];
-        let data = valid.sort();
-        for i in 1..data.size() {
-            if data.iloc(i-1) != data.iloc(i) { indices.push(i) }
-        }
-        let mut groups = vec![];
-        groups.push(&data.data[0..indices[0]]);
-        let mut chunks = indices.par_windows(2).map(|chunk| &data.data[chunk[0]..chunk[1]]).collect();
-        groups.append(&mut chunks);
-        groups.push(&data.data[indices[indices.len()-1]..data.data.len()]);
-
-        Series::new(groups.into_par_iter().max_by_key(|g| g.len()).unwrap().to_vec())
+    pub fn lt(&self, n: f64) -> Series {
+        self.indicator(|x| x < n)
     }
 
-    /// Calculates the variance of values inside the Series
+    /// Indicates values greater than or equal to `n`
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
-    ///
-    /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![2.5]);
-    /// assert_eq!(series.var(), expected);
+    /// use std::f64::NAN;
+    /// let series: Series = Series::new(vec![1.0, 2.0, NAN, 4.0, 5.0]);
+    /// assert_eq!(series.ge(2.0), Series::new(vec![0.0, 1.0, 0.0, 1.0, 1.0]));
     /// ```
-    pub fn var(&self) -> Series {
-        if self.size() < Series::LOWER_PAR_BOUND {
-            let valid = self.dropna();
-            if valid.is_empty() { return Series::zero() }
+    pub fn ge(&self, n: f64) -> Series {
+        self.indicator(|x| x >= n)
+    }
 
-            let n = valid.size() as f64;
-            let mean = valid.mean().iloc(0);
-            let variance = valid.data.into_iter().map(|x| pow(x-mean, 2)).sum::<f64>() / (n-1.0);
+    /// Indicates values less than or equal to `n`
+    ///
+    /// # Example
+    /// ```
+    /// use std::f64::NAN;
+    /// let series: Series = Series::new(vec![1.0, 2.0, NAN, 4.0, 5.0]);
+    /// assert_eq!(series.le(2.0), Series::new(vec![1.0, 1.0, 0.0, 0.0, 0.0]));
+    /// ```
+    pub fn le(&self, n: f64) -> Series {
+        self.indicator(|x| x <= n)
+    }
 
-            Series::new(vec![variance])
-        }
-        else {
-            let valid = self.dropna();
-            if valid.is_empty() { return Series::zero() }
+    /// Indicates values equal to `n`
+    ///
+    /// # Example
+    /// ```
+    /// use std::f64::NAN;
+    /// let series: Series = Series::new(vec![1.0, 2.0, NAN, 4.0, 5.0]);
+    /// assert_eq!(series.eq_elem(2.0), Series::new(vec![0.0, 1.0, 0.0, 0.0, 0.0]));
+    /// ```
+    pub fn eq_elem(&self, n: f64) -> Series {
+        self.indicator(|x| x == n)
+    }
 
-            let n = valid.size() as f64;
-            let mean = valid.mean().iloc(0);
-            let variance = valid.data.into_par_iter().map(|x| pow(x-mean, 2)).sum::<f64>() / (n-1.0);
+    /// Indicates values not equal to `n`. NaN is neither equal nor unequal to anything
+    ///
+    /// # Example
+    /// ```
+    /// use std::f64::NAN;
+    /// let series: Series = Series::new(vec![1.0, 2.0, NAN, 4.0, 5.0]);
+    /// assert_eq!(series.ne_elem(2.0), Series::new(vec![1.0, 0.0, 0.0, 1.0, 1.0]));
+    /// ```
+    pub fn ne_elem(&self, n: f64) -> Series {
+        self.indicator(|x| !x.is_nan() && x != n)
+    }
 
-            Series::new(vec![variance])
+    /// Checks whether two Series hold the same values to within `tol`, unlike `==` which needs
+    /// them to match exactly. NaNs count as equal to each other, and string columns are compared
+    /// by their text
+    ///
+    /// # Example
+    /// ```
+    /// use std::f64::NAN;
+    /// let series: Series = Series::new(vec![0.1, 0.2, NAN]);
+    /// let summed: Series = Series::new(vec![0.3, 0.4, NAN]).sub(0.2);
+    /// assert!(series.approx_eq(&summed, 1e-9));
+    /// assert!(!series.approx_eq(&Series::new(vec![0.1, 0.25, NAN]), 1e-9));
+    /// ```
+    pub fn approx_eq(&self, other: &Series, tol: f64) -> bool {
+        match (&self.strings, &other.strings) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => {
+                self.size() == other.size() &&
+                self.data.iter().zip(&other.data)
+                    .all(|(a, b)| a == b || (a - b).abs() <= tol || (a.is_nan() && b.is_nan()))
+            }
+            _ => false
         }
     }
 
-    /// Calculates the standard deviation of values inside the Series
+    /// Keeps the values where `mask` is non-zero and replaces the rest with `other`. A missing (NaN)
+    /// mask entry counts as false, the same as in `DataFrame::filter`
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// let data: Vec<f64> = vec![15.0, 42.0, 8.0, 21.0];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![1.58]);
-    /// assert_eq!(series.std(), expected);
+    /// let adults: Series = series.where_mask(series.ge(18.0), 0.0);
+    /// assert_eq!(adults, Series::new(vec![0.0, 42.0, 0.0, 21.0]));
+    ///
+    /// let mask: Series = Series::new(vec![f64::NAN, 1.0, 0.0, 1.0]);
+    /// assert_eq!(series.where_mask(mask, 0.0), Series::new(vec![0.0, 42.0, 0.0, 21.0]));
     /// ```
-    pub fn std(&self) -> Series {
-        let variance = self.var();
-        if variance.is_empty() { Series::zero(); }
-        Series::new(vec![variance.iloc(0).sqrt()])
+    pub fn where_mask(&self, mask: Series, other: f64) -> Series {
+        if self.size() != mask.size() { panic!("Series must have same dimensions"); }
+        Series::new(
+            self.data.par_iter()
+                .zip(mask.data.par_iter())
+                .map(|(&x, &m)| if m != 0.0 && !m.is_nan() { x } else { other })
+                .collect()
+        )
     }
 
-    /// Calculates the minimum of the values inside the Series
+    /// The inverse of `where_mask`: replaces the values where `mask` is non-zero with `other`. A
+    /// missing (NaN) mask entry counts as false, so the value is kept
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// let data: Vec<f64> = vec![15.0, 42.0, 8.0, 21.0];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![1.0]);
-    /// assert_eq!(series.min(), expected);
+    /// let minors: Series = series.mask(series.ge(18.0), 0.0);
+    /// assert_eq!(minors, Series::new(vec![15.0, 0.0, 8.0, 0.0]));
+    ///
+    /// let mask: Series = Series::new(vec![f64::NAN, 1.0, 0.0, 1.0]);
+    /// assert_eq!(series.mask(mask, 0.0), Series::new(vec![15.0, 0.0, 8.0, 0.0]));
     /// ```
-    pub fn min(&self) -> Series {
-        if self.is_empty() { Series::zero(); }
-
-        if self.size() < Series::LOWER_PAR_BOUND {
-            let dropna = self.dropna();
-            let m = (&dropna.data)
-                .into_iter()
-                .reduce(|x, y| if x < y {x} else {y})
-                .unwrap();
-            Series::new(vec![*m])
-        }
-        else {
-            let dropna = self.dropna();
-            let m = (&dropna.data)
-                .into_par_iter()
-                .reduce(|| &0.0, |x, y| if x < y {x} else {y});
-            Series::new(vec![*m])
-        }
+    pub fn mask(&self, mask: Series, other: f64) -> Series {
+        if self.size() != mask.size() { panic!("Series must have same dimensions"); }
+        Series::new(
+            self.data.par_iter()
+                .zip(mask.data.par_iter())
+                .map(|(&x, &m)| if m != 0.0 && !m.is_nan() { other } else { x })
+                .collect()
+        )
     }
 
-    /// Calculates the maximum of the values inside the Series
+    /// Returns a new Series with all missing values replaced by a value
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// use std::f64::NAN;
+    /// let data: Vec<f64> = vec![1.0, 2.0, NAN, 4.0, NAN];
+    /// let data_expected: Vec<f64> = vec![1.0, 2.0, 0.0, 4.0, 0.0];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![5.0]);
-    /// assert_eq!(series.max(), expected);
+    /// let expected: Series = Series::new(data_expected);
+    /// assert_eq!(series.fillna(0.0), expected);
     /// ```
-    pub fn max(&self) -> Series {
-        if self.is_empty() { Series::zero(); }
-
-        if self.size() < Series::LOWER_PAR_BOUND {
-            let dropna = self.dropna();
-            let m = (&dropna.data)
-                .into_iter()
-                .reduce(|x, y| if x > y {x} else {y})
-                .unwrap();
-            Series::new(vec![*m])
+    pub fn fillna(&self, value: f64) -> Series {
+        if self.size() < Series::par_threshold() {
+            Series::new(self.data.iter().map(|&x| if x.is_nan() { value } else { x }).collect())
         }
         else {
-            let dropna = self.dropna();
-            let m = (&dropna.data)
-                .into_par_iter()
-                .reduce(|| &0.0, |x, y| if x > y {x} else {y});
-            Series::new(vec![*m])
+            Series::new(self.data.par_iter().map(|&x| if x.is_nan() { value } else { x }).collect())
         }
     }
 
-    /*
-    /// Applies a function to all elements and returns a new Series
+    /// Fills each missing value with the last valid value before it. Leading NaNs have nothing
+    /// to carry forward, so they stay NaN
+    ///
+    /// Each value depends on the ones before it, so this is always done sequentially
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// use std::f64::NAN;
+    /// let data: Vec<f64> = vec![NAN, 1.0, NAN, NAN, 4.0, NAN];
     ///
-    /// let mult2 = |x: f64| -> f64 { x * 2.0 };
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![2.0, 4.0, 6.0, 8.0, 1.0]);
-    /// assert_eq!(series.apply(mult2), expected);
+    /// let filled: Series = series.ffill();
+    /// assert!(filled.iloc(0).is_nan());
+    /// assert_eq!(filled.slice(1, 6), Series::new(vec![1.0, 1.0, 1.0, 4.0, 4.0]));
     /// ```
-    pub fn apply(&self, f: fn(f64) -> f64) -> Series {
-        let applied = (&self.data).into_par_iter().map(|x| f(*x)).collect();
-        Series::new(applied)
+    pub fn ffill(&self) -> Series {
+        let mut last = f64::NAN;
+        Series::new(self.data.iter().map(|&x| {
+            if !x.is_nan() { last = x; }
+            last
+        }).collect())
     }
-    */
 
-    /// Element wise addition
+    /// Fills each missing value with the next valid value after it. Trailing NaNs have nothing
+    /// to carry backward, so they stay NaN
+    ///
+    /// Each value depends on the ones after it, so this is always done sequentially
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// use std::f64::NAN;
+    /// let data: Vec<f64> = vec![NAN, 1.0, NAN, NAN, 4.0, NAN];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![6.0, 7.0, 8.0, 9.0, 10.0]);
-    /// assert_eq!(series.plus(5), expected);
+    /// let filled: Series = series.bfill();
+    /// assert_eq!(filled.slice(0, 5), Series::new(vec![1.0, 1.0, 4.0, 4.0, 4.0]));
+    /// assert!(filled.iloc(5).is_nan());
     /// ```
-    pub fn plus(&self, n: f64) -> Series {
-        Series::new((&self.data).into_par_iter().map(|x| x + n).collect())
+    pub fn bfill(&self) -> Series {
+        let mut next = f64::NAN;
+        let mut filled: Vec<f64> = self.data.iter().rev().map(|&x| {
+            if !x.is_nan() { next = x; }
+            next
+        }).collect();
+        filled.reverse();
+        Series::new(filled)
     }
 
-    /// Element wise subtraction
+    /// Fills each gap of missing values by linearly interpolating between the valid values on
+    /// either side of it. Leading and trailing NaNs aren't between two values, so they stay NaN
+    /// (chain with `ffill`/`bfill` to fill those too)
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// use std::f64::NAN;
+    /// let data: Vec<f64> = vec![NAN, 1.0, NAN, NAN, 4.0, 2.0, NAN];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![-1.0, 0.0, 1.0, 2.0, 3.0]);
-    /// assert_eq!(series.sub(2), expected);
+    /// let filled: Series = series.interpolate();
+    /// assert!(filled.iloc(0).is_nan() && filled.iloc(6).is_nan());
+    /// assert_eq!(filled.slice(1, 6), Series::new(vec![1.0, 2.0, 3.0, 4.0, 2.0]));
     /// ```
-    pub fn sub(&self, n: f64) -> Series {
-        Series::new((&self.data).into_par_iter().map(|x| x - n).collect())
+    pub fn interpolate(&self) -> Series {
+        let mut filled = self.data.clone();
+        let valid: Vec<usize> = (0..self.size()).filter(|&i| !self.data[i].is_nan()).collect();
+
+        for w in valid.windows(2) {
+            let (lo, hi) = (w[0], w[1]);
+            let slope = (self.data[hi] - self.data[lo]) / (hi - lo) as f64;
+            for (step, x) in filled[lo + 1..hi].iter_mut().enumerate() {
+                *x = self.data[lo] + slope * (step + 1) as f64;
+            }
+        }
+        Series::new(filled)
     }
 
-    /// Element wise multiplication
+    /*
+    /// Indicates whether or not the Series contains any elements that satisfy a predicate
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// let data: Vec<f64> = vec![1.0, 3.0, 7.0, 14.0, 19.0];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![5.0, 10.0, 15.0, 20.0, 25.0, 30.0]);
-    /// assert_eq!(series.mult(5), expected);
+    /// let is_even = |x: f64| -> { x % 2 == 0 };
+    /// assert!(data.any(is_even));
     /// ```
-    pub fn mult(&self, n: f64) -> Series {
-        Series::new((&self.data).into_par_iter().map(|x| x * n).collect())
+    pub fn any(&self, pred: &dyn Fn(f64) -> bool) -> bool {
+        self.data.clone().into_par_iter().any(pred)
     }
+    */
 
-    /// Element wise division
+    /// Sorts the series
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// let data: Vec<f64> = vec![3.0, 1.0, 4.0, 1.0, 5.0, 9.0];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![0.5, 1.0, 1.5, 2.0, 2.5]);
-    /// assert_eq!(series.div(2), expected);
+    /// let expected: Series = Series::new(vec![1.0, 1.0, 3.0, 4.0, 5.0, 9.0]);
+    /// assert_eq!(series.sort(), expected);
     /// ```
-    pub fn div(&self, n: f64) -> Series {
-        Series::new((&self.data).into_par_iter().map(|x| x / n).collect())
+    pub fn sort(&self) -> Series {
+        let mut sorted: Vec<f64> = self.dropna().data;
+        sorted.par_sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Series::new(sorted)
     }
 
-    /// Calculates the cumulative/prefix sum of a Series
+    /// Finds the first position that breaks ascending order, or None if the Series is sorted
+    ///
+    /// A NaN can't be ordered, so it is reported as breaking the order
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// let data: Vec<f64> = vec![1.0, 2.0, 5.0, 3.0, 6.0];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![1.0, 3.0, 6.0, 10.0, 15.0]);
-    /// assert_eq!(series.cumsum(), expected);
+    /// assert_eq!(series.first_unsorted_index(), Some(3));
+    /// assert_eq!(series.sort().first_unsorted_index(), None);
     /// ```
-    pub fn cumsum(&self) -> Series {
-        // This looks awfully familiar
-        fn prefix_sum(xs: &Vec<f64>) -> (Vec<f64>, f64) {    
-            if xs.is_empty() { return (vec![], 0.0); }    
-
-            // Speeds it up quite a bit    
-            if xs.len() < 512 {    
-                let mut pfs: Vec<f64> = vec![0.0];
-                for i in 0..xs.len() {
-                    pfs.push(xs[0..i+1].iter().sum());    
-                }    
-                return (pfs[0..pfs.len()-1].to_vec(), pfs[pfs.len()-1])    
-            }    
-
-            let half = xs.len() / 2;
-            let (c_prefix, mut c_sum) = prefix_sum(
-                &(0..half).into_par_iter()
-                .map(|i| xs[i*2] + xs[i*2+1]) 
-                .collect::<Vec<f64>>()    
-              );    
-
-            let mut pfs: Vec<f64> = (0..half).into_par_iter() 
-                .flat_map(|i| vec![c_prefix[i], c_prefix[i]+xs[2*i]]) 
-                .collect();    
-
-            if xs.len() % 2 == 1 { pfs.push(c_sum); c_sum += xs[xs.len() - 1]; }    
-
-            (pfs, c_sum)    
-        }
-
-        let (mut pfs, c_sum) = prefix_sum(&self.data);
-        pfs.drain(0..1);
-        pfs.push(c_sum);
-        Series::new(pfs)
+    pub fn first_unsorted_index(&self) -> Option<usize> {
+        self.data.par_windows(2)
+            .position_first(|w| matches!(w[0].partial_cmp(&w[1]), None | Some(std::cmp::Ordering::Greater)))
+            .map(|i| i + 1)
     }
 
-    /// Joins the Series into string
+    /// Calculates the mean of the values inside the Series
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// let data: Vec<f64> = vec![1.0, 3.0, 7.0, 14.0, 19.0];
     ///
     /// let series: Series = Series::new(data);
-    /// assert_eq!(series.join(", "), "1, 2, 3, 4, 5".to_string());
+    /// let expected: Series = Series::new(vec![8.8]);
+    /// assert_eq!(series.mean(), expected);
     /// ```
-    pub fn join(&self, token: &str) -> String {
-        let joined: String = (&self.data).into_par_iter().map(|x| {
-            if x.is_nan() { "NaN".to_string() + token}
-            else { x.to_string() + token }
-        }).collect();
-
-        joined[0..joined.len() - token.len()].to_string()
+    pub fn mean(&self) -> Series {
+        if self.is_empty() { return Series::zero() }
+        Series::new(vec![self.sum().iloc(0) / self.size() as f64])
     }
 
-    /// Extracts a slice from the series
+    /// Calculates the q-th quantile (0.0 to 1.0) of the values inside the Series, linearly
+    /// interpolating between order statistics the same way numpy does by default
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0];
     ///
     /// let series: Series = Series::new(data);
-    /// let expected: Series = Series::new(vec![3.0, 4.0]);
-    /// assert_eq(series.slice(2, 4), expected);
+    /// assert_eq!(series.quantile(0.25), Series::new(vec![1.75]));
+    /// assert_eq!(series.quantile(1.0), Series::new(vec![4.0]));
     /// ```
-    pub fn slice(&self, start: usize, end: usize) -> Series {
-        let start = std::cmp::max(start, 0);
-        let end = std::cmp::min(end, self.size());
-        let slice = self.data[start..end].to_vec();
-        Series::new(slice)
+    pub fn quantile(&self, q: f64) -> Series {
+        if !(0.0..=1.0).contains(&q) { panic!("Quantile must be between 0 and 1"); }
+
+        let sorted = self.sort();
+        if sorted.is_empty() { return Series::zero() }
+
+        let pos = q * (sorted.size() - 1) as f64;
+        let lo = pos.floor() as usize;
+        let hi = pos.ceil() as usize;
+        let quantile = sorted.iloc(lo) + (sorted.iloc(hi) - sorted.iloc(lo)) * (pos - lo as f64);
+        Series::new(vec![quantile])
     }
 
+    /// Limits the values inside the Series to between `lower` and `upper`, replacing anything
+    /// outside with the nearest bound. NaNs stay NaN, and like pandas a NaN bound means there's
+    /// no bound on that side
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![-5.0, 1.0, f64::NAN, 7.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert_eq!(series.clip(0.0, 5.0).fillna(-1.0), Series::new(vec![0.0, 1.0, -1.0, 5.0]));
+    /// assert_eq!(series.clip(f64::NAN, 3.0).fillna(-1.0), Series::new(vec![-5.0, 1.0, -1.0, 3.0]));
+    /// ```
+    pub fn clip(&self, lower: f64, upper: f64) -> Series {
+        if lower > upper { panic!("Lower bound must not be above the upper bound"); }
+        // Comparisons with NaN are false, so NaN values and NaN bounds both fall through
+        self.map_values(|x| if x < lower { lower } else if x > upper { upper } else { x })
+    }
 
-    /// Computes the dot product of the Series and another
+    /// Limits the values inside the Series to between its `lower_q` and `upper_q` quantiles
+    /// (see `quantile` and `clip`), i.e. winsorizes it without knowing the bounds up front
     ///
     /// # Example
     /// ```
-    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
-    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
-    /// assert_eq!(a.dot(b).iloc(0), 12.0);
+    /// let data: Vec<f64> = vec![-100.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 100.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let clipped: Series = series.clip_quantile(0.0, 0.5);
+    /// assert_eq!(clipped.iloc(0), -100.0);
+    /// assert_eq!(clipped.iloc(9), 4.5);
     /// ```
-    pub fn dot(&self, other: Series) -> Series {
-        if self.size() != other.size() { panic!("Series must have same dimensions"); }
-        Series::new(
-            vec![
-                self.data.par_iter()
-                    .zip(other.data.par_iter())
-                    .map(|(&a, &b)| a * b)
-                    .sum()
-            ]
-        )
+    pub fn clip_quantile(&self, lower_q: f64, upper_q: f64) -> Series {
+        if !(0.0 <= lower_q && lower_q <= upper_q && upper_q <= 1.0) {
+            panic!("Quantiles must satisfy 0 <= lower_q <= upper_q <= 1");
+        }
+        // Nothing to clip to if there are no values
+        if self.count() == 0 { return self.clone() }
+
+        self.clip(self.quantile(lower_q).iloc(0), self.quantile(upper_q).iloc(0))
     }
 
-    /// Computes the vector sum of the Series and another
+    /// Calculates the median of the values inside the Series
     ///
     /// # Example
     /// ```
-    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
-    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
-    /// assert_eq!(a.vadd(b), Series::new(vec![5.0, -3.0, 9.0]));
+    /// let data: Vec<f64> = vec![1.0, 3.0, 7.0, 14.0, 19.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![7.0]);
+    /// assert_eq!(series.median(), expected);
     /// ```
-    pub fn vadd(&self, other: Series) -> Series {
-        if self.size() != other.size() { panic!("Series must have same dimensions"); }
-        Series::new(
-            self.data.par_iter()
-                .zip(other.data.par_iter())
-                .map(|(&a, &b)| a + b)
-                .collect()
-        )
+    pub fn median(&self) -> Series {
+        self.quantile(0.5)
     }
 
-    /// Computes vector subtraction of the Series and another
+    /// Calculates the mode of values inside the Series (the smallest one if there is a tie)
     ///
     /// # Example
     /// ```
-    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
-    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
-    /// assert_eq!(a.vsub(b), Series::new(vec![-3.0, 7.0, -3.0]));
+    /// let data: Vec<f64> = vec![1.0, 3.0, 3.0, 7.0, 14.0, 14.0 19.0, 19.0, 19.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![19.0]);
+    /// assert_eq!(series.mode(), expected);
     /// ```
-    pub fn vsub(&self, other: Series) -> Series {
-        if self.size() != other.size() { panic!("Series must have same dimensions"); }
-        Series::new(
-            self.data.par_iter()
-                .zip(other.data.par_iter())
-                .map(|(&a, &b)| a - b)
-                .collect()
-        )
+    pub fn mode(&self) -> Series {
+        // Groups come out in ascending order so the first of the largest is the smallest mode
+        let groups = self.group_indices();
+        let most = groups.iter().map(|(_, idx)| idx.len()).max();
+        match groups.iter().find(|(_, idx)| Some(idx.len()) == most) {
+            Some((value, _)) => Series::new(vec![*value]),
+            None => Series::zero()
+        }
     }
 
-    /// Computes the norm/magnitude of the Series
+    /// Counts the occurrences of each distinct value as a DataFrame with a `value` and a
+    /// `count` column, sorted by descending count (ties in ascending order of value)
+    ///
+    /// NaNs aren't counted unless `dropna` is false
     ///
     /// # Example
     /// ```
-    /// let a = Series::new(vec![2.0, -2.0, 3.0, -4.0]);
-    /// assert_eq!(a.norm().iloc(0), 33f64.sqrt());
+    /// let data: Vec<f64> = vec![2.0, 1.0, 2.0, f64::NAN, 3.0, 2.0, 1.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let counts: DataFrame = series.value_counts(true);
+    /// assert_eq!(counts.loc_col("value").unwrap(), Series::new(vec![2.0, 1.0, 3.0]));
+    /// assert_eq!(counts.loc_col("count").unwrap(), Series::new(vec![3.0, 2.0, 1.0]));
     /// ```
-    pub fn norm(&self) -> Series {
-        Series::new(
-            vec![
-                self.data.par_iter()
-                    .map(|&x| pow(x, 2))
-                    .sum::<f64>()
-                    .sqrt()
-            ]
+    #[args(dropna = "true")]
+    pub fn value_counts(&self, dropna: bool) -> DataFrame {
+        let mut counts: Vec<(f64, usize)> = self.group_indices().into_iter()
+            .map(|(value, idx)| (value, idx.len()))
+            .collect();
+
+        let n_missing = self.size() - self.count();
+        if !dropna && n_missing > 0 { counts.push((f64::NAN, n_missing)); }
+        counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+        let (values, counts): (Vec<f64>, Vec<f64>) = counts.into_iter().map(|(v, c)| (v, c as f64)).unzip();
+        DataFrame::new(
+            vec![Series::new(values), Series::new(counts)],
+            Some(vec!["value".to_string(), "count".to_string()])
         )
     }
 
-    /// Converts the Series to a Vector of f64
+    /// Extracts the distinct values inside the Series in the order they first appear
+    ///
+    /// Like pandas, NaN is kept as a single value (at its first position)
     ///
     /// # Example
     /// ```
-    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    /// let data: Vec<f64> = vec![3.0, 1.0, 3.0, f64::NAN, 2.0, 1.0, f64::NAN];
     ///
     /// let series: Series = Series::new(data);
-    /// assert_eq(series.to_vec(), data);
+    /// let unique: Series = series.unique();
+    /// assert_eq!(unique.size(), 4);
+    /// assert_eq!(unique.slice(0, 2), Series::new(vec![3.0, 1.0]));
+    /// assert!(unique.iloc(2).is_nan());
     /// ```
-    pub fn to_vec(&self) -> Vec<f64> {
-        self.data.to_vec()
+    pub fn unique(&self) -> Series {
+        // Groups keep their positions in order, so the first of each is where the value first appears
+        let mut firsts: Vec<usize> = self.group_indices().into_iter().map(|(_, idx)| idx[0]).collect();
+        if let Some(nan) = self.data.iter().position(|x| x.is_nan()) { firsts.push(nan); }
+        firsts.par_sort();
+        self.take(&firsts)
     }
 
-    fn __str__(&self) -> &'static str {
-        Box::leak(format!("[{}]", self.join(", ")).into_boxed_str())
-    }
-    fn __repr__(&self) -> &'static str {
-        Box::leak(format!("[{}]", self.join(", ")).into_boxed_str())
+    /// Counts the distinct non-NaN values inside the Series
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![3.0, 1.0, 3.0, f64::NAN, 2.0, 1.0, f64::NAN];
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert_eq!(series.nunique(), 3);
+    /// ```
+    pub fn nunique(&self) -> usize {
+        self.group_indices().len()
     }
-}
 
-macro_rules! from_num_type {
-    ($type:ty) => {
-        impl From<$type> for Series {
-            fn from(val: $type) -> Self {
-                Self { data: vec![val as f64] }
-            }
-        }
-    }
-}
+    /// Counts how many values fall into each of the bins given by `edges` as a DataFrame with
+    /// `bin_start`, `bin_end`, `count` and `frequency` columns
+    ///
+    /// Bins include their start but not their end, except the last bin which includes both.
+    /// Frequencies are relative to the number of non-NaN values, so values outside of the edges
+    /// aren't counted but still make up part of the total
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 2.5, 4.0, 9.0, 10.0, 12.0, f64::NAN];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let table: DataFrame = series.frequency_table(vec![0.0, 2.0, 5.0, 10.0]);
+    /// assert_eq!(table.loc_col("count").unwrap(), Series::new(vec![1.0, 3.0, 2.0]));
+    /// assert_eq!(table.loc_col("frequency").unwrap(), Series::new(vec![1.0 / 7.0, 3.0 / 7.0, 2.0 / 7.0]));
+    /// ```
+    pub fn frequency_table(&self, edges: Vec<f64>) -> DataFrame {
+        if edges.len() < 2 { panic!("Need at least two edges to make a bin") }
+        if edges.windows(2).any(|w| w[0].partial_cmp(&w[1]) != Some(std::cmp::Ordering::Less)) { panic!("Bin edges must be strictly increasing") }
 
-macro_rules! from_vec_type {
-    ($type:ty) => {
-        impl From<Vec<$type>> for Series {
-            fn from(val: Vec<$type>) -> Self {
-                Series { data: val.iter().map(|&x| x as f64).collect() }
-            }
+        let valid = self.dropna();
+        let n_bins = edges.len() - 1;
+        let mut counts = vec![0.0; n_bins];
+        for &x in &valid.data {
+            if x < edges[0] || x > edges[n_bins] { continue }
+            // Number of edges at or below x, clamped so the last edge falls into the last bin
+            let bin = (edges.partition_point(|&e| e <= x) - 1).min(n_bins - 1);
+            counts[bin] += 1.0;
         }
+
+        let total = valid.size() as f64;
+        let frequencies = counts.iter().map(|c| c / total).collect();
+        DataFrame::new(
+            vec![
+                Series::new(edges[..n_bins].to_vec()),
+                Series::new(edges[1..].to_vec()),
+                Series::new(counts),
+                Series::new(frequencies)
+            ],
+            Some(vec!["bin_start".to_string(), "bin_end".to_string(), "count".to_string(), "frequency".to_string()])
+        )
     }
-}
 
-macro_rules! from_vec_ref_type {
-    ($type:ty) => {
-        impl From<&Vec<$type>> for Series {
-            fn from(val: &Vec<$type>) -> Self {
-                Series { data: val.iter().map(|&x| x as f64).collect() }
-            }
+    /// Calculates the variance of values inside the Series
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![2.5]);
+    /// assert_eq!(series.var(), expected);
+    /// ```
+    pub fn var(&self) -> Series {
+        let valid = self.dropna();
+        if valid.is_empty() { return Series::zero() }
+
+        let n = valid.size() as f64;
+        let mean = valid.mean().iloc(0);
+        let squares: Vec<f64> = if valid.size() < Series::par_threshold() {
+            valid.data.into_iter().map(|x| pow(x-mean, 2)).collect()
         }
+        else {
+            valid.data.into_par_iter().map(|x| pow(x-mean, 2)).collect()
+        };
+
+        Series::new(vec![Series::stable_sum(&squares) / (n-1.0)])
     }
-}
 
-macro_rules! from_range_type {
-    ($type:ty) => {
-        impl From<Range<$type>> for Series {
-            fn from(val: Range<$type>) -> Self {
-                Self { data: val.map(|x| x as f64).collect() }
-            }
-        }
+    /// Calculates the standard deviation of values inside the Series
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![1.58]);
+    /// assert_eq!(series.std(), expected);
+    /// ```
+    pub fn std(&self) -> Series {
+        let variance = self.var();
+        if variance.is_empty() { Series::zero(); }
+        Series::new(vec![variance.iloc(0).sqrt()])
     }
-}
 
-macro_rules! from_range_incl_type {
-    ($type:ty) => {
-        impl From<RangeInclusive<$type>> for Series {
-            fn from(val: RangeInclusive<$type>) -> Self {
-                Self { data: val.map(|x| x as f64).collect() }
-            }
-        }
+    /// Calculates the sample skewness of values inside the Series
+    ///
+    /// Uses the adjusted Fisher-Pearson coefficient (same as pandas and `scipy.stats.skew` with
+    /// `bias=False`): `n / ((n-1)(n-2)) * sum(((x - mean) / std)^3)`, where `std` is the sample
+    /// standard deviation. Fewer than three values give NaN
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 10.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert!((series.skew().iloc(0) - 1.6971).abs() < 1e-4);
+    /// ```
+    pub fn skew(&self) -> Series {
+        let valid = self.dropna();
+        if valid.is_empty() { return Series::zero() }
+        if valid.size() < 3 { return Series::new(vec![f64::NAN]) }
+
+        let n = valid.size() as f64;
+        let cubes = valid.standardized_power_sum(3);
+        Series::new(vec![n / ((n - 1.0) * (n - 2.0)) * cubes])
     }
-}
 
-impl std::fmt::Display for Series {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{:?}", self.data)
+    /// Calculates the sample excess kurtosis of values inside the Series
+    ///
+    /// Uses the unbiased estimator (same as pandas and `scipy.stats.kurtosis` with `bias=False`):
+    /// `n(n+1) / ((n-1)(n-2)(n-3)) * sum(((x - mean) / std)^4) - 3(n-1)^2 / ((n-2)(n-3))`, where
+    /// `std` is the sample standard deviation. Fewer than four values give NaN
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 10.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert!((series.kurtosis().iloc(0) - 3.1520).abs() < 1e-4);
+    /// ```
+    pub fn kurtosis(&self) -> Series {
+        let valid = self.dropna();
+        if valid.is_empty() { return Series::zero() }
+        if valid.size() < 4 { return Series::new(vec![f64::NAN]) }
+
+        let n = valid.size() as f64;
+        let fourths = valid.standardized_power_sum(4);
+        let kurtosis = n * (n + 1.0) / ((n - 1.0) * (n - 2.0) * (n - 3.0)) * fourths
+            - 3.0 * (n - 1.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0));
+        Series::new(vec![kurtosis])
     }
-}
 
-impl Add for Series {
-    type Output = Self;
+    /// Standardizes each element as `(x - mean) / std`
+    ///
+    /// NaNs stay NaN, and if the standard deviation is zero every other element becomes 0.0
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, f64::NAN];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let zscores: Vec<f64> = series.zscore().to_vec();
+    /// assert_eq!(zscores[0..3], [-1.0, 0.0, 1.0]);
+    /// assert!(zscores[3].is_nan());
+    /// ```
+    pub fn zscore(&self) -> Series {
+        let valid = self.dropna();
+        if valid.is_empty() { return self.clone() }
 
-    fn add(self, other: Self) -> Self {
-        let mut data = self.data;
-        data.extend(other.data);
-        Self {
-            data
+        let mean = valid.mean().iloc(0);
+        let std = valid.std().iloc(0);
+        if std == 0.0 {
+            return Series::new((&self.data).into_par_iter().map(|x| if x.is_nan() { *x } else { 0.0 }).collect())
         }
+        Series::new((&self.data).into_par_iter().map(|x| (x - mean) / std).collect())
     }
-}
 
-impl Zero for Series {
-    fn zero() -> Self { Self { data: vec![] } }
-    fn is_zero(&self) -> bool { self.is_empty() }
-}
+    /// Scales each element to `[0, 1]` as `(x - min) / (max - min)`
+    ///
+    /// NaNs stay NaN, and if every other element is equal they all become 0.0
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![2.0, 4.0, 6.0, 10.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![0.0, 0.25, 0.5, 1.0]);
+    /// assert_eq!(series.normalize(), expected);
+    /// ```
+    pub fn normalize(&self) -> Series {
+        if self.dropna().is_empty() { return self.clone() }
 
-impl PartialEq for Series {
-    fn eq(&self, other: &Self) -> bool {
-        self.data == other.data
+        let min = self.min().iloc(0);
+        let range = self.max().iloc(0) - min;
+        if range == 0.0 {
+            return Series::new((&self.data).into_par_iter().map(|x| if x.is_nan() { *x } else { 0.0 }).collect())
+        }
+        Series::new((&self.data).into_par_iter().map(|x| (x - min) / range).collect())
     }
-}
 
-impl Eq for Series {}
+    /// Rebases the Series so that its first non-NaN element equals `base`, i.e. `x / first * base`
+    ///
+    /// NaNs stay NaN and a Series without any valid elements is returned as is
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![50.0, 75.0, 100.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![100.0, 150.0, 200.0]);
+    /// assert_eq!(series.index_to_base(100.0), expected);
+    /// ```
+    pub fn index_to_base(&self, base: f64) -> Series {
+        match self.data.iter().find(|x| !x.is_nan()) {
+            Some(&first) => Series::new((&self.data).into_par_iter().map(|x| x / first * base).collect()),
+            None => self.clone()
+        }
+    }
 
-from_num_type!(f64);
-from_num_type!(f32);
-from_num_type!(i8);
-from_num_type!(i16);
-from_num_type!(i32);
-from_num_type!(i64);
-from_num_type!(u8);
-from_num_type!(u16);
-from_num_type!(u32);
-from_num_type!(u64);
+    /// Summarises the outliers of the Series as a one-row DataFrame holding the lower and upper
+    /// bounds and the number of values below/above them
+    ///
+    /// `method` is either `"iqr"` (bounds are `Q1 - factor * IQR` and `Q3 + factor * IQR`) or
+    /// `"zscore"` (bounds are `mean -/+ factor * std`)
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![-20.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 50.0, 60.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let summary: DataFrame = series.outlier_summary("iqr", 1.5);
+    /// assert_eq!(summary.loc_col("lower").unwrap().iloc(0), -5.0);
+    /// assert_eq!(summary.loc_col("upper").unwrap().iloc(0), 15.0);
+    /// assert_eq!(summary.loc_col("n_low").unwrap().iloc(0), 1.0);
+    /// assert_eq!(summary.loc_col("n_high").unwrap().iloc(0), 2.0);
+    /// ```
+    pub fn outlier_summary(&self, method: &str, factor: f64) -> DataFrame {
+        let (lower, upper) = self.outlier_bounds(method, factor);
+        let n_low = self.data.par_iter().filter(|&&x| x < lower).count();
+        let n_high = self.data.par_iter().filter(|&&x| x > upper).count();
+
+        let header = vec!["lower".to_string(), "upper".to_string(), "n_low".to_string(), "n_high".to_string()];
+        let stats = vec![lower, upper, n_low as f64, n_high as f64];
+        DataFrame::new(stats.into_iter().map(Series::from).collect(), Some(header))
+    }
 
-from_vec_type!(f64);
-from_vec_type!(f32);
-from_vec_type!(i8);
-from_vec_type!(i16);
+    /// Calculates the minimum of the values inside the Series
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![1.0]);
+    /// assert_eq!(series.min(), expected);
+    /// ```
+    pub fn min(&self) -> Series {
+        let dropna = self.dropna();
+        if dropna.is_empty() { return Series::zero() }
+
+        if self.size() < Series::par_threshold() {
+            let m = dropna.data
+                .iter()
+                .reduce(|x, y| if x < y {x} else {y})
+                .unwrap();
+            Series::new(vec![*m])
+        }
+        else {
+            let m = dropna.data
+                .par_iter()
+                .reduce_with(|x, y| if x < y {x} else {y})
+                .unwrap();
+            Series::new(vec![*m])
+        }
+    }
+
+    /// Calculates the maximum of the values inside the Series
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![5.0]);
+    /// assert_eq!(series.max(), expected);
+    /// ```
+    pub fn max(&self) -> Series {
+        let dropna = self.dropna();
+        if dropna.is_empty() { return Series::zero() }
+
+        if self.size() < Series::par_threshold() {
+            let m = dropna.data
+                .iter()
+                .reduce(|x, y| if x > y {x} else {y})
+                .unwrap();
+            Series::new(vec![*m])
+        }
+        else {
+            let m = dropna.data
+                .par_iter()
+                .reduce_with(|x, y| if x > y {x} else {y})
+                .unwrap();
+            Series::new(vec![*m])
+        }
+    }
+
+    /// Finds the index of the first minimal non-NaN element
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![3.0, f64::NAN, 1.0, 4.0, 1.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert_eq!(series.argmin(), 2usize);
+    /// ```
+    pub fn argmin(&self) -> usize {
+        self.data.par_iter()
+            .enumerate()
+            .filter(|(_, x)| !x.is_nan())
+            .reduce_with(|a, b| if b.1 < a.1 {b} else {a})
+            .expect("Cannot find the minimum of an empty or all-NaN Series")
+            .0
+    }
+
+    /// Finds the index of the first maximal non-NaN element
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![3.0, f64::NAN, 5.0, 4.0, 5.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert_eq!(series.argmax(), 2usize);
+    /// ```
+    pub fn argmax(&self) -> usize {
+        self.data.par_iter()
+            .enumerate()
+            .filter(|(_, x)| !x.is_nan())
+            .reduce_with(|a, b| if b.1 > a.1 {b} else {a})
+            .expect("Cannot find the maximum of an empty or all-NaN Series")
+            .0
+    }
+
+    /// Summarises the Series as a one-row DataFrame holding the count (of non-NaN values),
+    /// mean, std, min, 25%, 50%, 75% and max
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0, f64::NAN];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let described: DataFrame = series.describe();
+    /// assert_eq!(described.loc_col("count").unwrap().iloc(0), 5.0);
+    /// assert_eq!(described.loc_col("25%").unwrap().iloc(0), 2.0);
+    /// println!("{}", described);
+    /// ```
+    pub fn describe(&self) -> DataFrame {
+        let quartile = |q: f64| self.quantile(q).to_vec().first().copied().unwrap_or(f64::NAN);
+        let summary = self.summary();
+        let stats = vec![
+            summary[0], summary[1], summary[2], summary[3],
+            quartile(0.25), quartile(0.5), quartile(0.75),
+            summary[4]
+        ];
+
+        let header = Series::DESCRIBE_STATS.iter().map(|s| s.to_string()).collect();
+        DataFrame::new(stats.into_iter().map(Series::from).collect(), Some(header))
+    }
+
+    /// Applies a Python function to all elements and returns a new Series
+    ///
+    /// The function is called once per element while holding the GIL, so unlike the rest of the
+    /// Series this runs sequentially and is much slower than the built-in element wise methods
+    ///
+    /// # Example
+    /// ```python
+    /// from rusty_pandas import Series
+    ///
+    /// series = Series([1.0, 2.0, 3.0, 4.0, 5.0])
+    /// assert series.apply(lambda x: x * 2.0).to_vec() == [2.0, 4.0, 6.0, 8.0, 10.0]
+    /// ```
+    pub fn apply(&self, py: Python, f: PyObject) -> PyResult<Series> {
+        let applied = self.data.iter()
+            .map(|x| f.call1(py, (*x,))?.extract(py))
+            .collect::<PyResult<Vec<f64>>>()?;
+        Ok(Series::new(applied))
+    }
+
+    /// Recodes whole-number values through a mapping, e.g. to relabel categorical codes.
+    /// Values that aren't in the mapping, or aren't whole numbers to begin with, become NaN
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// let mapping: HashMap<i64, f64> = HashMap::from([(1, 10.0), (2, 20.0)]);
+    /// let data: Vec<f64> = vec![1.0, 2.0, 1.5, 3.0, 1.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let recoded: Series = series.map_dict(mapping);
+    /// assert_eq!(recoded.fillna(-1.0), Series::new(vec![10.0, 20.0, -1.0, -1.0, 10.0]));
+    /// ```
+    pub fn map_dict(&self, mapping: HashMap<i64, f64>) -> Series {
+        self.map_values(|x| {
+            // Casting saturates, so check that the value survives the round trip instead
+            let key = x as i64;
+            if key as f64 != x { return f64::NAN }
+            *mapping.get(&key).unwrap_or(&f64::NAN)
+        })
+    }
+
+    /// Element wise addition
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![6.0, 7.0, 8.0, 9.0, 10.0]);
+    /// assert_eq!(series.plus(5), expected);
+    /// ```
+    pub fn plus(&self, n: f64) -> Series {
+        Series::new((&self.data).into_par_iter().map(|x| x + n).collect())
+    }
+
+    /// Element wise subtraction
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![-1.0, 0.0, 1.0, 2.0, 3.0]);
+    /// assert_eq!(series.sub(2), expected);
+    /// ```
+    pub fn sub(&self, n: f64) -> Series {
+        Series::new((&self.data).into_par_iter().map(|x| x - n).collect())
+    }
+
+    /// Element wise multiplication
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![5.0, 10.0, 15.0, 20.0, 25.0, 30.0]);
+    /// assert_eq!(series.mult(5), expected);
+    /// ```
+    pub fn mult(&self, n: f64) -> Series {
+        Series::new((&self.data).into_par_iter().map(|x| x * n).collect())
+    }
+
+    /// Element wise division
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![0.5, 1.0, 1.5, 2.0, 2.5]);
+    /// assert_eq!(series.div(2), expected);
+    /// ```
+    pub fn div(&self, n: f64) -> Series {
+        Series::new((&self.data).into_par_iter().map(|x| x / n).collect())
+    }
+
+    /// Element wise exponentiation. NaN stays NaN, and so does raising a negative value to a
+    /// fractional power since the result isn't real
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, -4.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![1.0, 4.0, 9.0, 16.0, 16.0]);
+    /// assert_eq!(series.pow(2.0), expected);
+    /// assert!(series.pow(0.5).iloc(4).is_nan());
+    /// ```
+    pub fn pow(&self, exp: f64) -> Series {
+        Series::new((&self.data).into_par_iter().map(|x| x.powf(exp)).collect())
+    }
+
+    /// Element wise square root. Negative values give NaN
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 4.0, 9.0, -1.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let roots: Series = series.sqrt();
+    /// assert_eq!(roots.slice(0, 3), Series::new(vec![1.0, 2.0, 3.0]));
+    /// assert!(roots.iloc(3).is_nan());
+    /// ```
+    pub fn sqrt(&self) -> Series {
+        self.map_values(f64::sqrt)
+    }
+
+    /// Element wise natural logarithm. Negative values give NaN and zero gives -inf
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, std::f64::consts::E, 0.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![0.0, 1.0, f64::NEG_INFINITY]);
+    /// assert_eq!(series.ln(), expected);
+    /// ```
+    pub fn ln(&self) -> Series {
+        self.map_values(f64::ln)
+    }
+
+    /// Element wise logarithm with respect to a base. Negative values give NaN and zero gives -inf
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 10.0, 1000.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![0.0, 1.0, 3.0]);
+    /// assert_eq!(series.log(10.0), expected);
+    /// ```
+    pub fn log(&self, base: f64) -> Series {
+        // log10 and log2 are more accurate than the general case for the two most common bases
+        if base == 10.0 { self.map_values(f64::log10) }
+        else if base == 2.0 { self.map_values(f64::log2) }
+        else { self.map_values(|x| x.log(base)) }
+    }
+
+    /// Element wise exponential function, i.e. `e^x`
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![0.0, 1.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![1.0, std::f64::consts::E]);
+    /// assert_eq!(series.exp(), expected);
+    /// ```
+    pub fn exp(&self) -> Series {
+        self.map_values(f64::exp)
+    }
+
+    /// Snaps each element to the nearest point of a regular grid starting at `origin` with
+    /// spacing `step` (halfway values round away from the origin)
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![0.2, 1.4, 2.6];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![0.5, 1.5, 2.5]);
+    /// assert_eq!(series.snap_to_grid(0.5, 1.0), expected);
+    /// ```
+    pub fn snap_to_grid(&self, origin: f64, step: f64) -> Series {
+        if step <= 0.0 { panic!("Grid step must be positive"); }
+        Series::new((&self.data).into_par_iter().map(|x| origin + ((x - origin) / step).round() * step).collect())
+    }
+
+    /// Calculates the cumulative/prefix sum of a Series
+    ///
+    /// Like pandas, NaNs stay NaN in place and the running total carries on past them with the
+    /// remaining values. Long Series are summed with a parallel scan, which adds the values in a
+    /// different order, so it can differ from a sequential running total by rounding error
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![1.0, 3.0, 6.0, 10.0, 15.0]);
+    /// assert_eq!(series.cumsum(), expected);
+    ///
+    /// let series: Series = Series::new(vec![1.0, f64::NAN, 3.0, 4.0]);
+    /// let summed: Series = series.cumsum();
+    /// assert!(summed.iloc(1).is_nan());
+    /// assert_eq!(summed.fillna(0.0), Series::new(vec![1.0, 0.0, 4.0, 8.0]));
+    /// ```
+    pub fn cumsum(&self) -> Series {
+        // This looks awfully familiar
+        fn prefix_sum(xs: &[f64]) -> (Vec<f64>, f64) {    
+            if xs.is_empty() { return (vec![], 0.0); }    
+
+            // A single running total beats splitting the work up until the Series is long
+            if xs.len() < Series::par_threshold() {
+                let mut pfs: Vec<f64> = Vec::with_capacity(xs.len());
+                let mut total = 0.0;
+                for x in xs {
+                    pfs.push(total);
+                    total += x;
+                }
+                return (pfs, total)
+            }    
+
+            let half = xs.len() / 2;
+            let (c_prefix, mut c_sum) = prefix_sum(
+                &(0..half).into_par_iter()
+                .map(|i| xs[i*2] + xs[i*2+1]) 
+                .collect::<Vec<f64>>()    
+              );    
+
+            let mut pfs: Vec<f64> = (0..half).into_par_iter() 
+                .flat_map(|i| vec![c_prefix[i], c_prefix[i]+xs[2*i]]) 
+                .collect();    
+
+            if xs.len() % 2 == 1 { pfs.push(c_sum); c_sum += xs[xs.len() - 1]; }    
+
+            (pfs, c_sum)    
+        }
+
+        if self.is_empty() { return Series::zero() }
+
+        // Sum as if the NaNs weren't there, then put them back where they were
+        let (mut pfs, c_sum) = prefix_sum(&self.fillna(0.0).data);
+        pfs.drain(0..1);
+        pfs.push(c_sum);
+        Series::new(pfs).where_mask(self.notna(), f64::NAN)
+    }
+
+    /// Ranks the values inside the Series from 1.0 for the smallest upwards. Tied values all
+    /// get the average of the ranks they span and NaNs stay NaN
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![30.0, 10.0, f64::NAN, 20.0, 10.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let ranks: Series = series.rank();
+    /// assert!(ranks.iloc(2).is_nan());
+    /// assert_eq!(ranks.fillna(0.0), Series::new(vec![4.0, 1.5, 0.0, 3.0, 1.5]));
+    /// ```
+    pub fn rank(&self) -> Series {
+        let mut ranks = vec![f64::NAN; self.size()];
+        let mut below = 0;
+        // Groups come out in ascending order, so each one follows every smaller value
+        for (_, idx) in self.group_indices() {
+            let average = below as f64 + (idx.len() + 1) as f64 / 2.0;
+            for &i in &idx { ranks[i] = average; }
+            below += idx.len();
+        }
+        Series::new(ranks)
+    }
+
+    /// Calculates the running trapezoidal integral of a Series sampled every `dx`, starting from
+    /// 0.0 at the first element
+    ///
+    /// NaNs are skipped: they stay NaN in the output and the trapezoid spans the gap between
+    /// the valid values on either side, i.e. the gap is linearly interpolated. This is a
+    /// sequential scan so there is no parallel path
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 1.0, 1.0, 1.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![0.0, 1.0, 2.0, 3.0]);
+    /// assert_eq!(series.cumintegrate(1.0), expected);
+    /// ```
+    pub fn cumintegrate(&self, dx: f64) -> Series {
+        let mut integral = vec![f64::NAN; self.size()];
+        let mut total = 0.0;
+        let mut prev: Option<usize> = None;
+
+        for (i, &x) in self.data.iter().enumerate() {
+            if x.is_nan() { continue; }
+            if let Some(p) = prev {
+                total += (self.data[p] + x) * 0.5 * (i - p) as f64 * dx;
+            }
+            integral[i] = total;
+            prev = Some(i);
+        }
+
+        Series::new(integral)
+    }
+
+    /// Calculates the percentile rank (0.0 to 1.0] of each element within its trailing window
+    ///
+    /// This is `rank` over each window, divided by the number of values in it. Ties get their
+    /// average rank and NaNs inside a window are ignored. The first `window - 1` elements, and NaN
+    /// elements, have no rank and are NaN
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 3.0, 2.0, 5.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let ranks: Vec<f64> = series.rolling_rank(3).to_vec();
+    /// assert!(ranks[0].is_nan() && ranks[1].is_nan());
+    /// assert_eq!(ranks[2..], [2.0 / 3.0, 1.0]);
+    /// ```
+    pub fn rolling_rank(&self, window: usize) -> Series {
+        if window == 0 { panic!("Window size must be positive"); }
+
+        Series::new((0..self.size()).into_par_iter().map(|i| {
+            let x = self.data[i];
+            if i + 1 < window || x.is_nan() { return f64::NAN }
+
+            let values = Series::new(self.data[i + 1 - window..=i].to_vec());
+            let valid = values.data.iter().filter(|y| !y.is_nan()).count();
+            values.rank().data[window - 1] / valid as f64
+        }).collect())
+    }
+
+    /// Finds each maximal run of consecutive values that are all within `tolerance` of each other
+    /// (i.e. the run's max minus its min is at most `tolerance`), as a DataFrame with the `start`
+    /// position, `length` and first `value` of the run
+    ///
+    /// Every non-NaN value belongs to a run (possibly of length 1), and NaNs break runs, so
+    /// plateaus such as a frozen sensor can be found by filtering on `length`. A slow drift
+    /// doesn't count as a plateau, since the run is measured against all of its values rather
+    /// than just the one before
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 5.0, 5.0, 5.01, 5.0, 5.0, 3.0, f64::NAN, 3.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let runs: DataFrame = series.constant_runs(0.05);
+    /// assert_eq!(runs.loc_col("start").unwrap(), Series::new(vec![0.0, 1.0, 2.0, 7.0, 9.0]));
+    /// assert_eq!(runs.loc_col("length").unwrap(), Series::new(vec![1.0, 1.0, 5.0, 1.0, 1.0]));
+    /// assert_eq!(runs.loc_col("value").unwrap(), Series::new(vec![1.0, 2.0, 5.0, 3.0, 3.0]));
+    /// ```
+    pub fn constant_runs(&self, tolerance: f64) -> DataFrame {
+        let (mut starts, mut lengths, mut values) = (vec![], vec![], vec![]);
+        // The smallest and largest value of the current run, if the previous element is in it
+        let mut bounds: Option<(f64, f64)> = None;
+        for (i, &x) in self.data.iter().enumerate() {
+            if x.is_nan() { bounds = None; continue }
+
+            match bounds {
+                Some((lo, hi)) if x.max(hi) - x.min(lo) <= tolerance => {
+                    *lengths.last_mut().unwrap() += 1.0;
+                    bounds = Some((x.min(lo), x.max(hi)));
+                },
+                _ => {
+                    starts.push(i as f64);
+                    lengths.push(1.0);
+                    values.push(x);
+                    bounds = Some((x, x));
+                }
+            }
+        }
+
+        DataFrame::new(
+            vec![Series::new(starts), Series::new(lengths), Series::new(values)],
+            Some(vec!["start".to_string(), "length".to_string(), "value".to_string()])
+        )
+    }
+
+    /// Run-length encodes the Series as the value of each run of equal elements and the length
+    /// of that run. Consecutive NaNs make up a run of their own
+    ///
+    /// # Example
+    /// ```
+    /// use std::f64::NAN;
+    /// let data: Vec<f64> = vec![1.0, 1.0, 1.0, NAN, NAN, 2.0, 1.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// let (values, lengths) = series.rle();
+    /// assert_eq!(lengths, vec![3, 2, 1, 1]);
+    ///
+    /// let restored: Series = Series::from_rle(values, lengths);
+    /// assert_eq!(restored.isna(), series.isna());
+    /// assert_eq!(restored.fillna(0.0), series.fillna(0.0));
+    /// ```
+    pub fn rle(&self) -> (Vec<f64>, Vec<usize>) {
+        let (mut values, mut lengths) = (vec![], vec![]);
+        for (i, &x) in self.data.iter().enumerate() {
+            let prev = if i > 0 { self.data[i - 1] } else { f64::NAN };
+            let continues = i > 0 && (x == prev || (x.is_nan() && prev.is_nan()));
+            if continues { *lengths.last_mut().unwrap() += 1; }
+            else {
+                values.push(x);
+                lengths.push(1);
+            }
+        }
+        (values, lengths)
+    }
+
+    /// Rebuilds a Series from its run-length encoding (see `rle`)
+    #[staticmethod]
+    pub fn from_rle(values: Vec<f64>, lengths: Vec<usize>) -> Series {
+        if values.len() != lengths.len() { panic!("Need exactly one length per value"); }
+        Series::new(
+            values.iter().zip(&lengths)
+                .flat_map(|(&x, &n)| std::iter::repeat_n(x, n))
+                .collect()
+        )
+    }
+
+    /// Joins the Series into string
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert_eq!(series.join(", "), "1, 2, 3, 4, 5".to_string());
+    /// ```
+    pub fn join(&self, token: &str) -> String {
+        if let Some(strings) = &self.strings { return strings.join(token) }
+        let joined: String = (&self.data).into_par_iter().map(|x| {
+            if x.is_nan() { "NaN".to_string() + token}
+            else { x.to_string() + token }
+        }).collect();
+
+        joined[0..joined.len() - token.len()].to_string()
+    }
+
+    /// Extracts a slice from the series
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    ///
+    /// let series: Series = Series::new(data);
+    /// let expected: Series = Series::new(vec![3.0, 4.0]);
+    /// assert_eq(series.slice(2, 4), expected);
+    /// ```
+    pub fn slice(&self, start: usize, end: usize) -> Series {
+        let start = std::cmp::max(start, 0);
+        let end = std::cmp::min(end, self.size());
+        let slice = self.data[start..end].to_vec();
+        Series {
+            data: slice,
+            strings: self.strings.as_ref().map(|strings| strings[start..end].to_vec())
+        }
+    }
+
+    /// Extracts the first `n` elements of the Series, or all of them if there are fewer than `n`
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert_eq!(series.head(2), Series::new(vec![1.0, 2.0]));
+    /// assert_eq!(series.head(10), series);
+    /// ```
+    pub fn head(&self, n: usize) -> Series {
+        self.slice(0, n)
+    }
+
+    /// Extracts the last `n` elements of the Series, or all of them if there are fewer than `n`
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert_eq!(series.tail(2), Series::new(vec![4.0, 5.0]));
+    /// assert_eq!(series.tail(10), series);
+    /// ```
+    pub fn tail(&self, n: usize) -> Series {
+        self.slice(self.size().saturating_sub(n), self.size())
+    }
+
+    /// Moves the values `periods` positions later (or earlier if negative), keeping the size the
+    /// same. Positions left without a value are NaN
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0];
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert_eq!(series.shift(1).fillna(0.0), Series::new(vec![0.0, 1.0, 2.0, 3.0]));
+    /// assert_eq!(series.shift(-2).fillna(0.0), Series::new(vec![3.0, 4.0, 0.0, 0.0]));
+    /// ```
+    pub fn shift(&self, periods: i64) -> Series {
+        let n = self.size() as i64;
+        Series::new((0..n).map(|i| {
+            let from = i - periods;
+            if (0..n).contains(&from) { self.data[from as usize] } else { f64::NAN }
+        }).collect())
+    }
+
+    /// Cuts the Series down to its first `n` values, or pads it with `fill` (NaN by default) up
+    /// to `n` values, e.g. to line Series up before `vadd` or `vsub`
+    ///
+    /// # Example
+    /// ```
+    /// let series: Series = Series::new(vec![1.0, 2.0, 3.0]);
+    /// assert_eq!(series.resize(2, f64::NAN), Series::new(vec![1.0, 2.0]));
+    /// assert_eq!(series.resize(5, 0.0), Series::new(vec![1.0, 2.0, 3.0, 0.0, 0.0]));
+    /// ```
+    ///
+    /// ```python
+    /// from rusty_pandas import Series
+    ///
+    /// series = Series([1.0, 2.0, 3.0])
+    /// assert series.resize(4, 0.0).to_vec() == [1.0, 2.0, 3.0, 0.0]
+    /// assert series.resize(4).count() == 3
+    /// ```
+    #[args(fill = "f64::NAN")]
+    pub fn resize(&self, n: usize, fill: f64) -> Series {
+        if n <= self.size() { self.slice(0, n) }
+        else { self.clone() + Series::new(vec![fill; n - self.size()]) }
+    }
+
+
+    /// Computes the dot product of the Series and another
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
+    /// assert_eq!(a.dot(b).iloc(0), 12.0);
+    /// ```
+    pub fn dot(&self, other: Series) -> Series {
+        self.dot_ref(&other)
+    }
+
+    /// Computes the vector sum of the Series and another
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
+    /// assert_eq!(a.vadd(b), Series::new(vec![5.0, -3.0, 9.0]));
+    /// ```
+    pub fn vadd(&self, other: Series) -> Series {
+        self.vadd_ref(&other)
+    }
+
+    /// Computes vector subtraction of the Series and another
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
+    /// assert_eq!(a.vsub(b), Series::new(vec![-3.0, 7.0, -3.0]));
+    /// ```
+    pub fn vsub(&self, other: Series) -> Series {
+        self.vsub_ref(&other)
+    }
+
+    /// Computes the elementwise (Hadamard) product of the Series and another
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
+    /// assert_eq!(a.vmul(b), Series::new(vec![4.0, -10.0, 18.0]));
+    /// ```
+    pub fn vmul(&self, other: Series) -> Series {
+        if self.size() != other.size() { panic!("Series must have same dimensions"); }
+        Series::new(
+            self.data.par_iter()
+                .zip(other.data.par_iter())
+                .map(|(&a, &b)| a * b)
+                .collect()
+        )
+    }
+
+    /// Computes the elementwise division of the Series by another. Dividing by zero gives
+    /// inf (or NaN for 0 / 0) rather than panicking
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Series::new(vec![4.0, -5.0, 0.0]);
+    /// assert_eq!(a.vdiv(b), Series::new(vec![0.25, -0.4, f64::INFINITY]));
+    /// ```
+    pub fn vdiv(&self, other: Series) -> Series {
+        if self.size() != other.size() { panic!("Series must have same dimensions"); }
+        Series::new(
+            self.data.par_iter()
+                .zip(other.data.par_iter())
+                .map(|(&a, &b)| a / b)
+                .collect()
+        )
+    }
+
+    /// Computes the norm/magnitude of the Series
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![2.0, -2.0, 3.0, -4.0]);
+    /// assert_eq!(a.norm().iloc(0), 33f64.sqrt());
+    /// ```
+    pub fn norm(&self) -> Series {
+        Series::new(
+            vec![
+                self.data.par_iter()
+                    .map(|&x| pow(x, 2))
+                    .sum::<f64>()
+                    .sqrt()
+            ]
+        )
+    }
+
+    /// Computes the Pearson correlation coefficient of the Series and another
+    ///
+    /// Positions where either Series is NaN are dropped before computing. Fewer than two
+    /// remaining pairs give NaN
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0, f64::NAN]);
+    /// let b = Series::new(vec![2.0, 4.0, 6.0, 1.0]);
+    /// assert_eq!(a.corr(b).iloc(0), 1.0);
+    /// ```
+    pub fn corr(&self, other: Series) -> Series {
+        if self.size() != other.size() { panic!("Series must have same dimensions"); }
+        let (a, b) = self.pairwise_dropna(&other);
+        if a.size() < 2 { return Series::new(vec![f64::NAN]) }
+
+        let cov = a.cov(b.clone()).iloc(0);
+        Series::new(vec![cov / (a.std().iloc(0) * b.std().iloc(0))])
+    }
+
+    /// Computes the sample covariance (with an `n-1` denominator) of the Series and another
+    ///
+    /// Positions where either Series is NaN are dropped before computing. Fewer than two
+    /// remaining pairs give NaN
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0, f64::NAN]);
+    /// let b = Series::new(vec![2.0, 4.0, 6.0, 1.0]);
+    /// assert_eq!(a.cov(b).iloc(0), 2.0);
+    /// ```
+    pub fn cov(&self, other: Series) -> Series {
+        if self.size() != other.size() { panic!("Series must have same dimensions"); }
+        let (a, b) = self.pairwise_dropna(&other);
+        if a.size() < 2 { return Series::new(vec![f64::NAN]) }
+
+        let n = a.size() as f64;
+        let centered_a = a.sub(a.mean().iloc(0));
+        let centered_b = b.sub(b.mean().iloc(0));
+        Series::new(vec![centered_a.dot_ref(&centered_b).iloc(0) / (n - 1.0)])
+    }
+
+    /// Computes the Pearson correlation of the Series with the other shifted by each lag from
+    /// `-max_lag` to `max_lag` (see `shift`), so position `i` holds lag `i - max_lag`. A peak
+    /// at a positive lag means the other Series leads this one
+    ///
+    /// The positions shifted out at each lag are dropped along with any NaNs, and lags that
+    /// leave fewer than two pairs give NaN
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![0.0, 0.0, 1.0, 5.0, 2.0, 0.0]);
+    /// let b = Series::new(vec![0.0, 1.0, 5.0, 2.0, 0.0, 0.0]);
+    /// let xcorr: Series = a.xcorr(b, 2);
+    /// assert_eq!(xcorr.size(), 5);
+    /// // b is a step ahead of a
+    /// assert_eq!(xcorr.argmax(), 3);
+    /// assert!((xcorr.iloc(3) - 1.0).abs() < 1e-12);
+    /// ```
+    pub fn xcorr(&self, other: Series, max_lag: usize) -> Series {
+        if self.size() != other.size() { panic!("Series must have same dimensions"); }
+        let max_lag = max_lag as i64;
+        Series::new(
+            (-max_lag..=max_lag).into_par_iter()
+                .map(|lag| self.corr(other.shift(lag)).iloc(0))
+                .collect()
+        )
+    }
+
+    /// Computes the Pearson correlation of the Series with itself shifted `lag` positions later
+    /// (see `shift`), dropping the positions shifted out. A lag that leaves fewer than two pairs
+    /// gives NaN
+    ///
+    /// # Example
+    /// ```
+    /// let series: Series = Series::new(vec![1.0, 2.0, 1.0, 2.0, 1.0, 2.0]);
+    /// assert!(series.autocorr(2).approx_eq(&Series::new(vec![1.0]), 1e-12));
+    /// assert!(series.autocorr(1).approx_eq(&Series::new(vec![-1.0]), 1e-12));
+    /// assert!(series.autocorr(6).iloc(0).is_nan());
+    /// ```
+    pub fn autocorr(&self, lag: usize) -> Series {
+        if lag >= self.size() { return Series::new(vec![f64::NAN]) }
+        self.corr(self.shift(lag as i64))
+    }
+
+    /// Converts the Series to a Vector of f64
+    ///
+    /// # Example
+    /// ```
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0]
+    ///
+    /// let series: Series = Series::new(data);
+    /// assert_eq(series.to_vec(), data);
+    /// ```
+    pub fn to_vec(&self) -> Vec<f64> {
+        self.data.to_vec()
+    }
+
+    /// Creates a string column. Values that happen to be numbers are still seen by the numeric
+    /// methods, while everything else is NaN to them
+    ///
+    /// # Example
+    /// ```
+    /// let series: Series = Series::from_strings(vec!["a".to_string(), "2".to_string()]);
+    /// assert!(series.is_string());
+    /// assert_eq!(series.to_strings(), vec!["a".to_string(), "2".to_string()]);
+    /// assert_eq!(series.fillna(0.0).to_vec(), vec![0.0, 2.0]);
+    /// ```
+    #[staticmethod]
+    pub fn from_strings(values: Vec<String>) -> Series {
+        let data = values.iter().map(|x| x.trim().parse::<f64>().unwrap_or(f64::NAN)).collect();
+        Series::with_strings(data, values)
+    }
+
+    /// Checks whether the Series is a string column, i.e. whether it keeps the original text
+    ///
+    /// # Example
+    /// ```
+    /// assert!(!Series::new(vec![1.0, 2.0]).is_string());
+    /// assert!(Series::from_strings(vec!["a".to_string()]).is_string());
+    /// ```
+    pub fn is_string(&self) -> bool {
+        self.strings.is_some()
+    }
+
+    /// Converts the Series to a Vector of strings, which is the original text of a string column
+    /// and the formatted values otherwise
+    ///
+    /// # Example
+    /// ```
+    /// let series: Series = Series::new(vec![1.5, f64::NAN]);
+    /// assert_eq!(series.to_strings(), vec!["1.5".to_string(), "NaN".to_string()]);
+    /// ```
+    pub fn to_strings(&self) -> Vec<String> {
+        (0..self.size()).map(|i| self.field(i)).collect()
+    }
+
+    /// Serializes the Series as a JSON array. JSON has no NaN, so missing values become null
+    ///
+    /// # Example
+    /// ```
+    /// let series: Series = Series::new(vec![1.0, 2.0, f64::NAN, 4.5]);
+    /// assert_eq!(series.to_json(), "[1.0, 2.0, null, 4.5]".to_string());
+    /// ```
+    pub fn to_json(&self) -> String {
+        json::number_array(&self.data)
+    }
+
+    /// Creates a Series from a JSON array of numbers, reading nulls as missing values. Malformed
+    /// JSON, or anything other than numbers and nulls in the array, raises a `ValueError`
+    ///
+    /// # Example
+    /// ```
+    /// let series: Series = Series::from_json("[1.0, 2, null, 4.5]").unwrap();
+    /// assert_eq!(series.fillna(0.0), Series::new(vec![1.0, 2.0, 0.0, 4.5]));
+    /// assert!(Series::from_json("[1, 2,]").is_err());
+    /// ```
+    #[staticmethod]
+    pub fn from_json(s: &str) -> PyResult<Series> {
+        json::parse_number_array(s).map(Series::new).map_err(PyValueError::new_err)
+    }
+
+    // Returned as an owned String so Python frees it, printing in a loop doesn't grow memory
+    fn __str__(&self) -> String {
+        format!("[{}]", self.join(", "))
+    }
+    fn __repr__(&self) -> String {
+        self.__str__()
+    }
+}
+
+// Helpers that PyO3 can't (or shouldn't) expose
+impl Series {
+    /// Computes the dot product of the Series and another by reference, so Rust callers
+    /// can keep using it afterwards (Python callers use `dot`)
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
+    /// let c = Series::new(vec![1.0, 1.0, 1.0]);
+    /// assert_eq!(a.dot_ref(&b).iloc(0) + a.dot_ref(&c).iloc(0), 18.0);
+    /// ```
+    pub fn dot_ref(&self, other: &Series) -> Series {
+        if self.size() != other.size() { panic!("Series must have same dimensions"); }
+        Series::new(
+            vec![
+                self.data.par_iter()
+                    .zip(other.data.par_iter())
+                    .map(|(&a, &b)| a * b)
+                    .sum()
+            ]
+        )
+    }
+
+    /// Computes the vector sum of the Series and another by reference, so Rust callers
+    /// can keep using it afterwards (Python callers use `vadd`)
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
+    /// assert_eq!(a.vadd_ref(&b), Series::new(vec![5.0, -3.0, 9.0]));
+    /// assert_eq!(b.vadd_ref(&a), a.vadd(b));
+    /// ```
+    pub fn vadd_ref(&self, other: &Series) -> Series {
+        if self.size() != other.size() { panic!("Series must have same dimensions"); }
+        Series::new(
+            self.data.par_iter()
+                .zip(other.data.par_iter())
+                .map(|(&a, &b)| a + b)
+                .collect()
+        )
+    }
+
+    /// Computes vector subtraction of the Series and another by reference, so Rust callers
+    /// can keep using it afterwards (Python callers use `vsub`)
+    ///
+    /// # Example
+    /// ```
+    /// let a = Series::new(vec![1.0, 2.0, 3.0]);
+    /// let b = Series::new(vec![4.0, -5.0, 6.0]);
+    /// assert_eq!(a.vsub_ref(&b), Series::new(vec![-3.0, 7.0, -3.0]));
+    /// assert_eq!(a.vsub_ref(&b), a.vsub(b));
+    /// ```
+    pub fn vsub_ref(&self, other: &Series) -> Series {
+        if self.size() != other.size() { panic!("Series must have same dimensions"); }
+        Series::new(
+            self.data.par_iter()
+                .zip(other.data.par_iter())
+                .map(|(&a, &b)| a - b)
+                .collect()
+        )
+    }
+
+    /// Sums values with a compensation term for the rounding error (see `sum`). Long inputs are
+    /// split into chunks that are summed in parallel, and their totals and compensations are
+    /// then summed the same way
+    pub(crate) fn stable_sum(values: &[f64]) -> f64 {
+        // Neumaier's variant of Kahan summation, giving the total and the error it left out
+        fn compensated(values: impl Iterator<Item = f64>) -> (f64, f64) {
+            let (mut total, mut compensation) = (0.0, 0.0);
+            for x in values {
+                let t = total + x;
+                compensation += if total.abs() >= x.abs() { (total - t) + x } else { (x - t) + total };
+                total = t;
+            }
+            (total, compensation)
+        }
+
+        let (total, compensation) = if values.len() < Series::par_threshold() {
+            compensated(values.iter().copied())
+        }
+        else {
+            let partials: Vec<(f64, f64)> = values.par_chunks(Series::par_threshold().max(1))
+                .map(|chunk| compensated(chunk.iter().copied()))
+                .collect();
+            compensated(partials.into_iter().flat_map(|(t, c)| [t, c]))
+        };
+        total + compensation
+    }
+
+    /// Names of the statistics produced by `summary`, in order
+    pub(crate) const SUMMARY_STATS: [&'static str; 5] = ["count", "mean", "std", "min", "max"];
+
+    /// Names of the statistics produced by `describe`, in order
+    pub(crate) const DESCRIBE_STATS: [&'static str; 8] = ["count", "mean", "std", "min", "25%", "50%", "75%", "max"];
+
+    /// Computes count/mean/std/min/max over the non-NaN values
+    pub(crate) fn summary(&self) -> Vec<f64> {
+        let valid = self.dropna();
+        if valid.is_empty() { return vec![0.0, f64::NAN, f64::NAN, f64::NAN, f64::NAN] }
+
+        vec![
+            valid.size() as f64,
+            valid.mean().iloc(0),
+            valid.std().iloc(0),
+            valid.min().iloc(0),
+            valid.max().iloc(0)
+        ]
+    }
+
+    /// Groups the positions of the non-NaN values by value, in ascending order of value
+    pub(crate) fn group_indices(&self) -> Vec<(f64, Vec<usize>)> {
+        // Floats can't be hashed so sort the positions and split them into runs instead
+        let mut order: Vec<usize> = (0..self.size()).filter(|&i| !self.data[i].is_nan()).collect();
+        order.par_sort_by(|&a, &b| self.data[a].partial_cmp(&self.data[b]).unwrap());
+
+        order.chunk_by(|&a, &b| self.data[a] == self.data[b])
+            .map(|group| (self.data[group[0]], group.to_vec()))
+            .collect()
+    }
+
+    /// Sums `((x - mean) / std)^power` over the values, which must not contain NaN
+    fn standardized_power_sum(&self, power: i32) -> f64 {
+        let mean = self.mean().iloc(0);
+        let std = self.std().iloc(0);
+
+        if self.size() < Series::par_threshold() {
+            self.data.iter().map(|x| ((x - mean) / std).powi(power)).sum()
+        }
+        else {
+            self.data.par_iter().map(|x| ((x - mean) / std).powi(power)).sum()
+        }
+    }
+
+    /// Drops every position where either Series is NaN, keeping the two aligned
+    pub(crate) fn pairwise_dropna(&self, other: &Series) -> (Series, Series) {
+        let (a, b): (Vec<f64>, Vec<f64>) = self.data.par_iter()
+            .zip(other.data.par_iter())
+            .filter(|(a, b)| !a.is_nan() && !b.is_nan())
+            .map(|(&a, &b)| (a, b))
+            .unzip();
+        (Series::new(a), Series::new(b))
+    }
+
+    /// Computes the bounds outside of which values count as outliers (see `outlier_summary`)
+    pub(crate) fn outlier_bounds(&self, method: &str, factor: f64) -> (f64, f64) {
+        let valid = self.dropna();
+        if valid.is_empty() { return (f64::NAN, f64::NAN) }
+
+        match method {
+            "iqr" => {
+                let q1 = valid.quantile(0.25).iloc(0);
+                let q3 = valid.quantile(0.75).iloc(0);
+                (q1 - factor * (q3 - q1), q3 + factor * (q3 - q1))
+            },
+            "zscore" => {
+                let mean = valid.mean().iloc(0);
+                let std = valid.std().iloc(0);
+                (mean - factor * std, mean + factor * std)
+            },
+            _ => panic!("Unknown outlier method {}", method)
+        }
+    }
+
+    /// Builds a 1.0/0.0 mask of the elements that satisfy a predicate, like `isna`/`notna`
+    pub(crate) fn indicator(&self, pred: impl Fn(f64) -> bool + Sync + Send) -> Series {
+        if self.size() < Series::par_threshold() {
+            Series::new(self.data.iter().map(|&x| pred(x) as i32 as f64).collect())
+        }
+        else {
+            Series::new(self.data.par_iter().map(|&x| pred(x) as i32 as f64).collect())
+        }
+    }
+
+    /// Iterates over the elements without copying them like `to_vec` does
+    ///
+    /// # Example
+    /// ```
+    /// let series: Series = Series::new(vec![1.0, 2.0, 3.0]);
+    /// let doubled: Vec<f64> = series.iter().map(|x| x * 2.0).collect();
+    /// assert_eq!(doubled, vec![2.0, 4.0, 6.0]);
+    ///
+    /// let mut total = 0.0;
+    /// for x in &series { total += x; }
+    /// assert_eq!(total, 6.0);
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, f64> {
+        self.data.iter()
+    }
+
+    /// Applies a function to every element, in parallel for large Series
+    pub(crate) fn map_values(&self, f: impl Fn(f64) -> f64 + Sync + Send) -> Series {
+        if self.size() < Series::par_threshold() {
+            Series::new(self.data.iter().map(|&x| f(x)).collect())
+        }
+        else {
+            Series::new(self.data.par_iter().map(|&x| f(x)).collect())
+        }
+    }
+
+    /// Finds the positions that would sort the Series, with NaNs last whichever the direction.
+    /// The sort is stable so equal values keep their order
+    pub(crate) fn argsort(&self, ascending: bool) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.size()).collect();
+        order.par_sort_by(|&a, &b| {
+            let (x, y) = (self.data[a], self.data[b]);
+            match (x.is_nan(), y.is_nan()) {
+                (true, true) => std::cmp::Ordering::Equal,
+                (true, false) => std::cmp::Ordering::Greater,
+                (false, true) => std::cmp::Ordering::Less,
+                _ if ascending => x.partial_cmp(&y).unwrap(),
+                _ => y.partial_cmp(&x).unwrap()
+            }
+        });
+        order
+    }
+
+    /// Gathers the values at the given positions into a new Series
+    pub(crate) fn take(&self, idx: &[usize]) -> Series {
+        Series {
+            data: idx.iter().map(|&i| self.data[i]).collect(),
+            strings: self.strings.as_ref().map(|strings| idx.iter().map(|&i| strings[i].clone()).collect())
+        }
+    }
+
+    /// Creates a string column from its text and the numeric view of it
+    pub(crate) fn with_strings(data: Vec<f64>, strings: Vec<String>) -> Series {
+        Series { data, strings: Some(strings) }
+    }
+
+    /// Formats a single value the same way `join` does, or gives its text for a string column
+    pub(crate) fn field(&self, idx: usize) -> String {
+        match &self.strings {
+            Some(strings) => strings[idx].clone(),
+            None if self.data[idx].is_nan() => "NaN".to_string(),
+            None => self.data[idx].to_string()
+        }
+    }
+
+    /// Estimates the heap bytes held by the values (8 per f64) plus the text of a string column
+    pub(crate) fn heap_bytes(&self) -> usize {
+        let text = self.strings.as_ref().map_or(0, |strings| strings.iter().map(|s| s.len()).sum());
+        self.data.len() * std::mem::size_of::<f64>() + text
+    }
+}
+
+macro_rules! from_num_type {
+    ($type:ty) => {
+        impl From<$type> for Series {
+            fn from(val: $type) -> Self {
+                Series::new(vec![val as f64])
+            }
+        }
+    }
+}
+
+macro_rules! from_vec_type {
+    ($type:ty) => {
+        impl From<Vec<$type>> for Series {
+            fn from(val: Vec<$type>) -> Self {
+                Series::new(val.iter().map(|&x| x as f64).collect())
+            }
+        }
+    }
+}
+
+macro_rules! from_vec_ref_type {
+    ($type:ty) => {
+        impl From<&Vec<$type>> for Series {
+            fn from(val: &Vec<$type>) -> Self {
+                Series::new(val.iter().map(|&x| x as f64).collect())
+            }
+        }
+    }
+}
+
+macro_rules! from_range_type {
+    ($type:ty) => {
+        impl From<Range<$type>> for Series {
+            fn from(val: Range<$type>) -> Self {
+                Series::new(val.map(|x| x as f64).collect())
+            }
+        }
+    }
+}
+
+macro_rules! from_range_incl_type {
+    ($type:ty) => {
+        impl From<RangeInclusive<$type>> for Series {
+            fn from(val: RangeInclusive<$type>) -> Self {
+                Series::new(val.map(|x| x as f64).collect())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Series {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.strings {
+            Some(strings) => write!(f, "{:?}", strings),
+            None => write!(f, "{:?}", self.data)
+        }
+    }
+}
+
+impl Add for Series {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        // Only keep text if there was any, in which case the numbers become text too
+        let strings = if self.is_string() || other.is_string() {
+            Some(self.to_strings().into_iter().chain(other.to_strings()).collect())
+        }
+        else { None };
+
+        let mut data = self.data;
+        data.extend(other.data);
+        Self {
+            data,
+            strings
+        }
+    }
+}
+
+impl Zero for Series {
+    fn zero() -> Self { Series::new(vec![]) }
+    fn is_zero(&self) -> bool { self.is_empty() }
+}
+
+impl PartialEq for Series {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.strings, &other.strings) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => self.data == other.data,
+            _ => false
+        }
+    }
+}
+
+impl Eq for Series {}
+
+/// Indexes into the Series like `iloc`, panicking if the index is out of bounds
+///
+/// # Example
+/// ```
+/// let series: Series = Series::new(vec![1.0, 2.0, 3.0]);
+/// assert_eq!(series[1], series.iloc(1));
+/// ```
+impl Index<usize> for Series {
+    type Output = f64;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        self.data.get(idx).expect("Not a valid index")
+    }
+}
+
+impl IntoIterator for Series {
+    type Item = f64;
+    type IntoIter = std::vec::IntoIter<f64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Series {
+    type Item = &'a f64;
+    type IntoIter = std::slice::Iter<'a, f64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+// Serialized as a sequence of numbers with null in place of NaN, so it survives formats like JSON.
+// A string column is serialized as its text instead
+#[cfg(feature = "serde")]
+impl serde::Serialize for Series {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match &self.strings {
+            Some(strings) => serializer.collect_seq(strings),
+            None => serializer.collect_seq(self.data.iter().map(|&x| if x.is_nan() { None } else { Some(x) }))
+        }
+    }
+}
+
+// Any string in the sequence makes it a string column, like `read_json` does
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Series {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Element {
+            Number(Option<f64>),
+            Text(String)
+        }
+
+        let elements: Vec<Element> = serde::Deserialize::deserialize(deserializer)?;
+        // Text keeps the numeric view `from_strings` would give it
+        let data: Vec<f64> = elements.iter()
+            .map(|e| match e {
+                Element::Number(x) => x.unwrap_or(f64::NAN),
+                Element::Text(s) => s.trim().parse::<f64>().unwrap_or(f64::NAN)
+            })
+            .collect();
+        if !elements.iter().any(|e| matches!(e, Element::Text(_))) { return Ok(Series::new(data)) }
+
+        let strings = elements.into_iter()
+            .map(|e| match e {
+                Element::Text(s) => s,
+                Element::Number(Some(x)) => x.to_string(),
+                Element::Number(None) => String::new()
+            })
+            .collect();
+        Ok(Series::with_strings(data, strings))
+    }
+}
+
+from_num_type!(f64);
+from_num_type!(f32);
+from_num_type!(i8);
+from_num_type!(i16);
+from_num_type!(i32);
+from_num_type!(i64);
+from_num_type!(u8);
+from_num_type!(u16);
+from_num_type!(u32);
+from_num_type!(u64);
+
+from_vec_type!(f64);
+from_vec_type!(f32);
+from_vec_type!(i8);
+from_vec_type!(i16);
 from_vec_type!(i32);
 from_vec_type!(i64);
 from_vec_type!(u8);
@@ -727,31 +2315,1000 @@ from_vec_type!(u16);
 from_vec_type!(u32);
 from_vec_type!(u64);
 
-from_vec_ref_type!(f64);
-from_vec_ref_type!(f32);
-from_vec_ref_type!(i8);
-from_vec_ref_type!(i16);
-from_vec_ref_type!(i32);
-from_vec_ref_type!(i64);
-from_vec_ref_type!(u8);
-from_vec_ref_type!(u16);
-from_vec_ref_type!(u32);
-from_vec_ref_type!(u64);
+from_vec_ref_type!(f64);
+from_vec_ref_type!(f32);
+from_vec_ref_type!(i8);
+from_vec_ref_type!(i16);
+from_vec_ref_type!(i32);
+from_vec_ref_type!(i64);
+from_vec_ref_type!(u8);
+from_vec_ref_type!(u16);
+from_vec_ref_type!(u32);
+from_vec_ref_type!(u64);
+
+from_range_type!(i8);
+from_range_type!(i16);
+from_range_type!(i32);
+from_range_type!(i64);
+from_range_type!(u8);
+from_range_type!(u16);
+from_range_type!(u32);
+from_range_type!(u64);
+
+from_range_incl_type!(i8);
+from_range_incl_type!(i16);
+from_range_incl_type!(i32);
+from_range_incl_type!(i64);
+from_range_incl_type!(u8);
+from_range_incl_type!(u16);
+from_range_incl_type!(u32);
+from_range_incl_type!(u64);
+
+/// Runs `f` with the parallel threshold set to `threshold`, so tests can reach both the
+/// sequential and the parallel paths. The threshold is global, so these runs take turns
+#[cfg(test)]
+pub(crate) fn with_par_threshold<T>(threshold: usize, f: impl FnOnce() -> T) -> T {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    // Puts the old threshold back even if `f` panics
+    struct Restore(usize);
+    impl Drop for Restore {
+        fn drop(&mut self) { Series::set_par_threshold(self.0) }
+    }
+
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let _restore = Restore(Series::par_threshold());
+    Series::set_par_threshold(threshold);
+    f()
+}
+
+#[cfg(test)]
+mod min_max_tests {
+    use super::*;
+
+    // The extremes of each case, checked on both the sequential and the parallel path
+    fn check(data: Vec<f64>, min: f64, max: f64) {
+        for threshold in [usize::MAX, 1] {
+            let series: Series = Series::new(data.clone());
+            let (lo, hi) = with_par_threshold(threshold, || (series.min(), series.max()));
+            assert_eq!(lo, Series::new(vec![min]), "min of {:?} with threshold {}", data, threshold);
+            assert_eq!(hi, Series::new(vec![max]), "max of {:?} with threshold {}", data, threshold);
+        }
+    }
+
+    #[test]
+    fn all_negative() {
+        check(vec![-5.0, -2.0, -9.0], -9.0, -2.0);
+    }
+
+    #[test]
+    fn all_positive() {
+        check(vec![5.0, 2.0, 9.0], 2.0, 9.0);
+    }
+
+    #[test]
+    fn single() {
+        check(vec![7.0], 7.0, 7.0);
+    }
+
+    #[test]
+    fn skips_nan() {
+        check(vec![f64::NAN, 5.0, 2.0, f64::NAN, 9.0], 2.0, 9.0);
+    }
+
+    #[test]
+    fn above_threshold() {
+        // Large enough for the parallel path at the default threshold too
+        let series: Series = Series::from(-20000..-1);
+        assert!(series.size() >= Series::par_threshold());
+        check(series.to_vec(), -20000.0, -2.0);
+    }
+
+    #[test]
+    fn empty_or_all_nan() {
+        assert!(Series::new(vec![f64::NAN, f64::NAN]).min().is_empty());
+        assert!(Series::new(vec![]).max().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod cumsum_tests {
+    use super::*;
+
+    #[test]
+    fn interior_nan() {
+        let series: Series = Series::new(vec![1.0, f64::NAN, 3.0, f64::NAN, f64::NAN, 4.0]);
+        for threshold in [usize::MAX, 1] {
+            let summed: Series = with_par_threshold(threshold, || series.cumsum());
+            assert!(summed.iloc(1).is_nan() && summed.iloc(3).is_nan() && summed.iloc(4).is_nan());
+            assert_eq!(summed.fillna(0.0), Series::new(vec![1.0, 0.0, 4.0, 0.0, 0.0, 8.0]));
+        }
+    }
+
+    #[test]
+    fn leading_and_trailing_nan() {
+        let series: Series = Series::new(vec![f64::NAN, 2.0, 3.0, f64::NAN]);
+        let summed: Series = series.cumsum();
+        assert!(summed.iloc(0).is_nan() && summed.iloc(3).is_nan());
+        assert_eq!(summed.iloc(1), 2.0);
+        assert_eq!(summed.iloc(2), 5.0);
+    }
+
+    #[test]
+    fn paths_agree() {
+        // Odd and even lengths split differently in the parallel scan
+        for len in [1, 2, 7, 64, 101] {
+            let series: Series = Series::new((0..len).map(|i| if i % 5 == 3 { f64::NAN } else { (i % 7) as f64 - 3.0 }).collect());
+            let sequential: Series = with_par_threshold(usize::MAX, || series.cumsum());
+            let parallel: Series = with_par_threshold(1, || series.cumsum());
+            assert!(sequential.approx_eq(&parallel, 0.0), "length {}", len);
+        }
+    }
+
+    #[test]
+    fn ten_thousand() {
+        // Whole numbers add up exactly in any order, so both paths give the closed form
+        let series: Series = Series::from(0..10_000);
+        let expected: Series = Series::new((0..10_000i64).map(|i| (i * (i + 1) / 2) as f64).collect());
+        let running: Series = with_par_threshold(usize::MAX, || series.cumsum());
+        let recursive: Series = with_par_threshold(1, || series.cumsum());
+        assert_eq!(running, expected);
+        assert_eq!(recursive, expected);
+
+        // Fractions only differ by rounding error
+        let series: Series = Series::new((0..10_000).map(|i| (i as f64 * 0.37).sin()).collect());
+        let running: Series = with_par_threshold(usize::MAX, || series.cumsum());
+        let recursive: Series = with_par_threshold(1, || series.cumsum());
+        assert!(running.approx_eq(&recursive, 1e-9));
+    }
+}
+
+#[cfg(test)]
+mod snap_to_grid_tests {
+    use super::*;
+
+    #[test]
+    fn offset_grid() {
+        let series: Series = Series::new(vec![0.2, 1.4, 2.6, -0.7]);
+        assert_eq!(series.snap_to_grid(0.5, 1.0), Series::new(vec![0.5, 1.5, 2.5, -0.5]));
+    }
+
+    #[test]
+    fn halfway_rounds_away_from_origin() {
+        let series: Series = Series::new(vec![1.0, 0.0]);
+        assert_eq!(series.snap_to_grid(0.5, 1.0), Series::new(vec![1.5, -0.5]));
+    }
+
+    #[test]
+    fn nan_stays_nan() {
+        let snapped: Series = Series::new(vec![f64::NAN, 3.2]).snap_to_grid(0.0, 2.0);
+        assert!(snapped.iloc(0).is_nan());
+        assert_eq!(snapped.iloc(1), 4.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Grid step must be positive")]
+    fn rejects_zero_step() {
+        Series::new(vec![1.0]).snap_to_grid(0.0, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod describe_tests {
+    use super::*;
+
+    fn stat(described: &DataFrame, name: &str) -> f64 {
+        described.loc_col(name).unwrap().iloc(0)
+    }
+
+    #[test]
+    fn all_statistics() {
+        let described: DataFrame = Series::new(vec![5.0, 1.0, f64::NAN, 3.0, 2.0, 4.0]).describe();
+        assert_eq!(described.shape(), (1, Series::DESCRIBE_STATS.len()));
+
+        assert_eq!(stat(&described, "count"), 5.0);
+        assert_eq!(stat(&described, "mean"), 3.0);
+        assert!((stat(&described, "std") - 2.5f64.sqrt()).abs() < 1e-12);
+        assert_eq!(stat(&described, "min"), 1.0);
+        assert_eq!(stat(&described, "25%"), 2.0);
+        assert_eq!(stat(&described, "50%"), 3.0);
+        assert_eq!(stat(&described, "75%"), 4.0);
+        assert_eq!(stat(&described, "max"), 5.0);
+    }
+
+    #[test]
+    fn all_nan() {
+        let described: DataFrame = Series::new(vec![f64::NAN, f64::NAN]).describe();
+        assert_eq!(stat(&described, "count"), 0.0);
+        assert!(Series::DESCRIBE_STATS[1..].iter().all(|s| stat(&described, s).is_nan()));
+    }
+}
+
+#[cfg(test)]
+mod skew_kurtosis_tests {
+    use super::*;
+
+    // Reference values from scipy.stats.skew/kurtosis with bias=False
+    #[test]
+    fn matches_scipy() {
+        let series: Series = Series::new(vec![2.0, 8.0, 0.0, 4.0, 1.0, 9.0, 9.0, 0.0]);
+        assert!((series.skew().iloc(0) - 0.3305821804079746).abs() < 1e-12);
+        assert!((series.kurtosis().iloc(0) - -2.098602258096087).abs() < 1e-12);
+
+        let series: Series = Series::new(vec![1.0, 2.0, 3.0, 4.0, 10.0]);
+        assert!((series.skew().iloc(0) - 1.6970562748477143).abs() < 1e-12);
+        assert!((series.kurtosis().iloc(0) - 3.152).abs() < 1e-12);
+    }
+
+    #[test]
+    fn drops_nan() {
+        let series: Series = Series::new(vec![1.0, f64::NAN, 2.0, 3.0, 4.0, 10.0]);
+        assert!((series.skew().iloc(0) - 1.6970562748477143).abs() < 1e-12);
+        assert!((series.kurtosis().iloc(0) - 3.152).abs() < 1e-12);
+    }
 
-from_range_type!(i8);
-from_range_type!(i16);
-from_range_type!(i32);
-from_range_type!(i64);
-from_range_type!(u8);
-from_range_type!(u16);
-from_range_type!(u32);
-from_range_type!(u64);
+    #[test]
+    fn symmetric_has_no_skew() {
+        assert!(Series::new(vec![1.0, 2.0, 3.0, 4.0, 5.0]).skew().iloc(0).abs() < 1e-12);
+    }
 
-from_range_incl_type!(i8);
-from_range_incl_type!(i16);
-from_range_incl_type!(i32);
-from_range_incl_type!(i64);
-from_range_incl_type!(u8);
-from_range_incl_type!(u16);
-from_range_incl_type!(u32);
-from_range_incl_type!(u64);
+    #[test]
+    fn too_few_values() {
+        assert!(Series::new(vec![f64::NAN]).skew().is_empty());
+        assert!(Series::new(vec![]).kurtosis().is_empty());
+        assert!(Series::new(vec![1.0, 2.0]).skew().iloc(0).is_nan());
+        assert!(Series::new(vec![1.0, 2.0, 3.0]).kurtosis().iloc(0).is_nan());
+    }
+}
+
+#[cfg(test)]
+mod cumintegrate_tests {
+    use super::*;
+
+    #[test]
+    fn constant_ones() {
+        let series: Series = Series::new(vec![1.0; 5]);
+        assert_eq!(series.cumintegrate(1.0), Series::new(vec![0.0, 1.0, 2.0, 3.0, 4.0]));
+    }
+
+    #[test]
+    fn linear_is_exact() {
+        // The trapezoid rule is exact for a straight line, here y = t sampled every 0.5
+        let series: Series = Series::new(vec![0.0, 0.5, 1.0, 1.5]);
+        assert_eq!(series.cumintegrate(0.5), Series::new(vec![0.0, 0.125, 0.5, 1.125]));
+    }
+
+    #[test]
+    fn nan_gap_is_spanned() {
+        let integral: Series = Series::new(vec![1.0, f64::NAN, 3.0, 3.0]).cumintegrate(1.0);
+        assert!(integral.iloc(1).is_nan());
+        assert_eq!(integral.fillna(-1.0), Series::new(vec![0.0, -1.0, 4.0, 7.0]));
+    }
+
+    #[test]
+    fn leading_nan() {
+        let integral: Series = Series::new(vec![f64::NAN, 2.0, 2.0]).cumintegrate(1.0);
+        assert!(integral.iloc(0).is_nan());
+        assert_eq!(integral.iloc(1), 0.0);
+        assert_eq!(integral.iloc(2), 2.0);
+    }
+}
+
+#[cfg(test)]
+mod corr_tests {
+    use super::*;
+
+    #[test]
+    fn perfect_correlation() {
+        let a: Series = Series::new(vec![1.0, 2.0, 3.0, 4.0]);
+        assert!((a.corr(a.map_values(|x| 3.0 * x + 1.0)).iloc(0) - 1.0).abs() < 1e-12);
+        assert!((a.corr(a.map_values(|x| -2.0 * x)).iloc(0) + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn known_value() {
+        let a: Series = Series::new(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        let b: Series = Series::new(vec![2.0, 1.0, 4.0, 3.0, 5.0]);
+        assert!((a.corr(b).iloc(0) - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn drops_nan_pairwise() {
+        // The NaN on either side takes its partner out too, leaving the known pairs above
+        let a: Series = Series::new(vec![1.0, 2.0, f64::NAN, 3.0, 4.0, 5.0, 100.0]);
+        let b: Series = Series::new(vec![2.0, 1.0, -50.0, 4.0, 3.0, 5.0, f64::NAN]);
+        assert!((a.corr(b).iloc(0) - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn too_few_pairs() {
+        let a: Series = Series::new(vec![1.0, f64::NAN]);
+        let b: Series = Series::new(vec![2.0, 3.0]);
+        assert!(a.corr(b).iloc(0).is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "Series must have same dimensions")]
+    fn rejects_different_lengths() {
+        Series::new(vec![1.0, 2.0]).corr(Series::new(vec![1.0, 2.0, 3.0]));
+    }
+}
+
+#[cfg(test)]
+mod cov_tests {
+    use super::*;
+
+    #[test]
+    fn sample_covariance() {
+        let a: Series = Series::new(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+        let b: Series = Series::new(vec![2.0, 1.0, 4.0, 3.0, 5.0]);
+        assert_eq!(a.cov(b).iloc(0), 2.0);
+    }
+
+    #[test]
+    fn with_itself_is_variance() {
+        let a: Series = Series::new(vec![3.0, 1.0, 4.0, 1.0, 5.0]);
+        assert!((a.cov(a.clone()).iloc(0) - a.var().iloc(0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn drops_nan_pairwise() {
+        let a: Series = Series::new(vec![1.0, f64::NAN, 2.0, 3.0, 4.0, 5.0]);
+        let b: Series = Series::new(vec![2.0, 7.0, 1.0, 4.0, f64::NAN, 3.0]);
+        // Leaves the pairs (1, 2), (2, 1), (3, 4) and (5, 3)
+        let expected: f64 = Series::new(vec![1.0, 2.0, 3.0, 5.0]).cov(Series::new(vec![2.0, 1.0, 4.0, 3.0])).iloc(0);
+        assert_eq!(a.cov(b).iloc(0), expected);
+        assert!((expected - 3.5 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn too_few_pairs() {
+        assert!(Series::new(vec![1.0]).cov(Series::new(vec![2.0])).iloc(0).is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "Series must have same dimensions")]
+    fn rejects_different_lengths() {
+        Series::new(vec![1.0]).cov(Series::new(vec![1.0, 2.0]));
+    }
+}
+
+#[cfg(test)]
+mod first_unsorted_index_tests {
+    use super::*;
+
+    #[test]
+    fn single_out_of_order_element() {
+        assert_eq!(Series::new(vec![1.0, 2.0, 5.0, 3.0, 6.0]).first_unsorted_index(), Some(3));
+        assert_eq!(Series::new(vec![1.0, 2.0, 3.0, 0.0]).first_unsorted_index(), Some(3));
+    }
+
+    #[test]
+    fn sorted() {
+        assert_eq!(Series::new(vec![1.0, 2.0, 2.0, 3.0]).first_unsorted_index(), None);
+        assert_eq!(Series::new(vec![4.0]).first_unsorted_index(), None);
+        assert_eq!(Series::new(vec![]).first_unsorted_index(), None);
+    }
+
+    #[test]
+    fn first_of_several() {
+        let mut data: Vec<f64> = (0..20_000).map(|i| i as f64).collect();
+        data.swap(12_000, 12_001);
+        data.swap(15_000, 15_001);
+        assert_eq!(Series::new(data).first_unsorted_index(), Some(12_001));
+    }
+
+    #[test]
+    fn nan_breaks_the_order() {
+        assert_eq!(Series::new(vec![1.0, f64::NAN, 3.0]).first_unsorted_index(), Some(1));
+    }
+}
+
+#[cfg(test)]
+mod zscore_tests {
+    use super::*;
+
+    #[test]
+    fn standardizes() {
+        let zscores: Series = Series::new(vec![2.0, 4.0, 6.0, 8.0]).zscore();
+        let std: f64 = (20.0f64 / 3.0).sqrt();
+        assert!(zscores.approx_eq(&Series::new(vec![-3.0 / std, -1.0 / std, 1.0 / std, 3.0 / std]), 1e-12));
+        assert!(zscores.mean().iloc(0).abs() < 1e-12);
+        assert!((zscores.std().iloc(0) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn nan_stays_nan() {
+        let zscores: Series = Series::new(vec![1.0, f64::NAN, 2.0, 3.0]).zscore();
+        assert!(zscores.approx_eq(&Series::new(vec![-1.0, f64::NAN, 0.0, 1.0]), 1e-12));
+    }
+
+    #[test]
+    fn zero_std_gives_zeros() {
+        let zscores: Series = Series::new(vec![5.0, f64::NAN, 5.0]).zscore();
+        assert!(zscores.approx_eq(&Series::new(vec![0.0, f64::NAN, 0.0]), 0.0));
+    }
+
+    #[test]
+    fn all_nan() {
+        assert!(Series::new(vec![f64::NAN, f64::NAN]).zscore().iter().all(|x| x.is_nan()));
+    }
+}
+
+#[cfg(test)]
+mod outlier_summary_tests {
+    use super::*;
+
+    fn stat(summary: &DataFrame, name: &str) -> f64 {
+        summary.loc_col(name).unwrap().iloc(0)
+    }
+
+    #[test]
+    fn iqr_counts_each_tail() {
+        // One low outlier and two high ones, plus a NaN that counts as neither
+        let series: Series = Series::new(vec![-20.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 50.0, 60.0, f64::NAN]);
+        let summary: DataFrame = series.outlier_summary("iqr", 1.5);
+        assert_eq!(stat(&summary, "lower"), -5.0);
+        assert_eq!(stat(&summary, "upper"), 15.0);
+        assert_eq!(stat(&summary, "n_low"), 1.0);
+        assert_eq!(stat(&summary, "n_high"), 2.0);
+    }
+
+    #[test]
+    fn zscore_bounds() {
+        let series: Series = Series::new(vec![0.0, 0.0, 0.0, 0.0, 10.0]);
+        let summary: DataFrame = series.outlier_summary("zscore", 1.0);
+        assert!((stat(&summary, "lower") - (2.0 - 20f64.sqrt())).abs() < 1e-12);
+        assert!((stat(&summary, "upper") - (2.0 + 20f64.sqrt())).abs() < 1e-12);
+        assert_eq!(stat(&summary, "n_low"), 0.0);
+        assert_eq!(stat(&summary, "n_high"), 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown outlier method mad")]
+    fn rejects_unknown_method() {
+        Series::new(vec![1.0, 2.0]).outlier_summary("mad", 3.0);
+    }
+}
+
+#[cfg(test)]
+mod normalize_tests {
+    use super::*;
+
+    #[test]
+    fn scales_to_unit_range() {
+        let normalized: Series = Series::new(vec![-10.0, 0.0, 10.0, 30.0]).normalize();
+        assert_eq!(normalized, Series::new(vec![0.0, 0.25, 0.5, 1.0]));
+    }
+
+    #[test]
+    fn nan_stays_nan() {
+        let normalized: Series = Series::new(vec![2.0, f64::NAN, 4.0, 3.0]).normalize();
+        assert!(normalized.approx_eq(&Series::new(vec![0.0, f64::NAN, 1.0, 0.5]), 0.0));
+    }
+
+    #[test]
+    fn constant_gives_zeros() {
+        let normalized: Series = Series::new(vec![7.0, 7.0, f64::NAN]).normalize();
+        assert!(normalized.approx_eq(&Series::new(vec![0.0, 0.0, f64::NAN]), 0.0));
+    }
+
+    #[test]
+    fn all_nan_is_unchanged() {
+        assert!(Series::new(vec![f64::NAN]).normalize().iloc(0).is_nan());
+        assert!(Series::new(vec![]).normalize().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod argmin_argmax_tests {
+    use super::*;
+
+    #[test]
+    fn first_of_ties() {
+        let series: Series = Series::new(vec![3.0, 1.0, 5.0, 1.0, 5.0]);
+        assert_eq!(series.argmin(), 1);
+        assert_eq!(series.argmax(), 2);
+    }
+
+    #[test]
+    fn skips_nan() {
+        let series: Series = Series::new(vec![f64::NAN, 4.0, f64::NAN, -2.0]);
+        assert_eq!(series.argmin(), 3);
+        assert_eq!(series.argmax(), 1);
+    }
+
+    #[test]
+    fn long_series() {
+        // Ties far apart end up in different parallel chunks
+        let mut data: Vec<f64> = vec![0.0; 50_000];
+        data[30_000] = -1.0;
+        data[40_000] = -1.0;
+        data[10_000] = 1.0;
+        data[45_000] = 1.0;
+        let series: Series = Series::new(data);
+        assert_eq!(series.argmin(), 30_000);
+        assert_eq!(series.argmax(), 10_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot find the minimum of an empty or all-NaN Series")]
+    fn argmin_all_nan() {
+        Series::new(vec![f64::NAN, f64::NAN]).argmin();
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot find the maximum of an empty or all-NaN Series")]
+    fn argmax_empty() {
+        Series::new(vec![]).argmax();
+    }
+}
+
+#[cfg(test)]
+mod value_counts_tests {
+    use super::*;
+
+    fn counts(series: &Series, dropna: bool) -> (Series, Series) {
+        let counts: DataFrame = series.value_counts(dropna);
+        (counts.loc_col("value").unwrap(), counts.loc_col("count").unwrap())
+    }
+
+    #[test]
+    fn sorted_by_descending_count() {
+        let series: Series = Series::new(vec![2.0, 1.0, 2.0, f64::NAN, 3.0, 2.0, 1.0]);
+        let (values, n) = counts(&series, true);
+        assert_eq!(values, Series::new(vec![2.0, 1.0, 3.0]));
+        assert_eq!(n, Series::new(vec![3.0, 2.0, 1.0]));
+    }
+
+    #[test]
+    fn ties_in_ascending_order() {
+        let series: Series = Series::new(vec![5.0, -1.0, 3.0, 5.0, 3.0, -1.0]);
+        let (values, n) = counts(&series, true);
+        assert_eq!(values, Series::new(vec![-1.0, 3.0, 5.0]));
+        assert_eq!(n, Series::new(vec![2.0, 2.0, 2.0]));
+    }
+
+    #[test]
+    fn counts_nan_unless_dropped() {
+        let series: Series = Series::new(vec![f64::NAN, 1.0, f64::NAN, f64::NAN, 1.0, 4.0]);
+        let (values, n) = counts(&series, false);
+        assert!(values.iloc(0).is_nan());
+        assert_eq!(values.iloc(1), 1.0);
+        assert_eq!(values.iloc(2), 4.0);
+        assert_eq!(n, Series::new(vec![3.0, 2.0, 1.0]));
+
+        let (values, n) = counts(&series, true);
+        assert_eq!(values, Series::new(vec![1.0, 4.0]));
+        assert_eq!(n, Series::new(vec![2.0, 1.0]));
+    }
+
+    #[test]
+    fn empty() {
+        let (values, n) = counts(&Series::new(vec![]), false);
+        assert!(values.is_empty() && n.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod unique_tests {
+    use super::*;
+
+    #[test]
+    fn first_seen_order() {
+        let series: Series = Series::new(vec![3.0, 1.0, 3.0, 2.0, 1.0, -4.0]);
+        assert_eq!(series.unique(), Series::new(vec![3.0, 1.0, 2.0, -4.0]));
+        assert_eq!(series.nunique(), 4);
+    }
+
+    #[test]
+    fn nan_is_one_value_for_unique_only() {
+        let series: Series = Series::new(vec![f64::NAN, 3.0, f64::NAN, 3.0, 5.0]);
+        let unique: Series = series.unique();
+        assert_eq!(unique.size(), 3);
+        assert!(unique.iloc(0).is_nan());
+        assert_eq!(unique.slice(1, 3), Series::new(vec![3.0, 5.0]));
+        assert_eq!(series.nunique(), 2);
+    }
+
+    #[test]
+    fn empty_and_all_nan() {
+        assert!(Series::new(vec![]).unique().is_empty());
+        assert_eq!(Series::new(vec![f64::NAN, f64::NAN]).unique().size(), 1);
+        assert_eq!(Series::new(vec![f64::NAN]).nunique(), 0);
+    }
+}
+
+#[cfg(test)]
+mod frequency_table_tests {
+    use super::*;
+
+    #[test]
+    fn uneven_edges() {
+        let series: Series = Series::new(vec![1.0, 2.0, 2.5, 4.0, 9.0, 10.0, 12.0, -1.0, f64::NAN]);
+        let table: DataFrame = series.frequency_table(vec![0.0, 2.0, 5.0, 10.0]);
+        assert_eq!(table.loc_col("bin_start").unwrap(), Series::new(vec![0.0, 2.0, 5.0]));
+        assert_eq!(table.loc_col("bin_end").unwrap(), Series::new(vec![2.0, 5.0, 10.0]));
+        assert_eq!(table.loc_col("count").unwrap(), Series::new(vec![1.0, 3.0, 2.0]));
+        // Out of range values are part of the total, NaNs aren't
+        assert_eq!(table.loc_col("frequency").unwrap(), Series::new(vec![1.0 / 8.0, 3.0 / 8.0, 2.0 / 8.0]));
+    }
+
+    #[test]
+    fn bins_include_their_start() {
+        let series: Series = Series::new(vec![0.0, 1.0, 1.0, 3.0]);
+        let table: DataFrame = series.frequency_table(vec![0.0, 1.0, 3.0]);
+        // Only the last bin includes its end
+        assert_eq!(table.loc_col("count").unwrap(), Series::new(vec![1.0, 3.0]));
+        assert_eq!(table.loc_col("frequency").unwrap(), Series::new(vec![0.25, 0.75]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Bin edges must be strictly increasing")]
+    fn rejects_unsorted_edges() {
+        Series::new(vec![1.0]).frequency_table(vec![0.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Need at least two edges to make a bin")]
+    fn rejects_single_edge() {
+        Series::new(vec![1.0]).frequency_table(vec![0.0]);
+    }
+}
+
+#[cfg(test)]
+mod fillna_tests {
+    use super::*;
+
+    #[test]
+    fn replaces_only_nan() {
+        let series: Series = Series::new(vec![1.0, f64::NAN, -3.0, f64::NAN]);
+        for threshold in [usize::MAX, 1] {
+            let filled: Series = with_par_threshold(threshold, || series.fillna(0.5));
+            assert_eq!(filled, Series::new(vec![1.0, 0.5, -3.0, 0.5]));
+        }
+    }
+
+    #[test]
+    fn keeps_length() {
+        let series: Series = Series::new(vec![f64::NAN; 3]);
+        assert_eq!(series.fillna(7.0), Series::new(vec![7.0; 3]));
+        assert!(Series::new(vec![]).fillna(1.0).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod index_to_base_tests {
+    use super::*;
+
+    #[test]
+    fn rebases_to_100() {
+        let series: Series = Series::new(vec![50.0, 75.0, 100.0]);
+        assert_eq!(series.index_to_base(100.0), Series::new(vec![100.0, 150.0, 200.0]));
+    }
+
+    #[test]
+    fn first_valid_value_is_the_base() {
+        let rebased: Series = Series::new(vec![f64::NAN, 20.0, f64::NAN, 5.0]).index_to_base(1.0);
+        assert!(rebased.approx_eq(&Series::new(vec![f64::NAN, 1.0, f64::NAN, 0.25]), 0.0));
+    }
+
+    #[test]
+    fn all_nan_is_unchanged() {
+        assert!(Series::new(vec![f64::NAN, f64::NAN]).index_to_base(100.0).iter().all(|x| x.is_nan()));
+        assert!(Series::new(vec![]).index_to_base(100.0).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod ffill_bfill_tests {
+    use super::*;
+
+    const NAN: f64 = f64::NAN;
+
+    #[test]
+    fn ffill_carries_forward() {
+        let filled: Series = Series::new(vec![NAN, 1.0, NAN, NAN, 4.0, NAN]).ffill();
+        assert!(filled.approx_eq(&Series::new(vec![NAN, 1.0, 1.0, 1.0, 4.0, 4.0]), 0.0));
+    }
+
+    #[test]
+    fn bfill_carries_backward() {
+        let filled: Series = Series::new(vec![NAN, 1.0, NAN, NAN, 4.0, NAN]).bfill();
+        assert!(filled.approx_eq(&Series::new(vec![1.0, 1.0, 4.0, 4.0, 4.0, NAN]), 0.0));
+    }
+
+    #[test]
+    fn without_gaps_is_unchanged() {
+        let series: Series = Series::new(vec![3.0, 1.0, 2.0]);
+        assert_eq!(series.ffill(), series);
+        assert_eq!(series.bfill(), series);
+    }
+
+    #[test]
+    fn all_nan_stays_nan() {
+        let series: Series = Series::new(vec![NAN, NAN]);
+        assert!(series.ffill().iter().chain(series.bfill().iter()).all(|x| x.is_nan()));
+    }
+}
+
+#[cfg(test)]
+mod interpolate_tests {
+    use super::*;
+
+    const NAN: f64 = f64::NAN;
+
+    #[test]
+    fn fills_interior_gaps_linearly() {
+        let filled: Series = Series::new(vec![1.0, NAN, NAN, 4.0, 2.0, NAN, 0.0]).interpolate();
+        assert_eq!(filled, Series::new(vec![1.0, 2.0, 3.0, 4.0, 2.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    fn leading_and_trailing_stay_nan() {
+        let filled: Series = Series::new(vec![NAN, NAN, 2.0, NAN, 4.0, NAN]).interpolate();
+        assert!(filled.approx_eq(&Series::new(vec![NAN, NAN, 2.0, 3.0, 4.0, NAN]), 0.0));
+    }
+
+    #[test]
+    fn chains_with_ffill_and_bfill() {
+        let filled: Series = Series::new(vec![NAN, 2.0, NAN, 4.0, NAN]).interpolate().ffill().bfill();
+        assert_eq!(filled, Series::new(vec![2.0, 2.0, 3.0, 4.0, 4.0]));
+    }
+
+    #[test]
+    fn single_valid_value() {
+        let filled: Series = Series::new(vec![NAN, 5.0, NAN]).interpolate();
+        assert!(filled.approx_eq(&Series::new(vec![NAN, 5.0, NAN]), 0.0));
+    }
+}
+
+#[cfg(test)]
+mod count_tests {
+    use super::*;
+
+    #[test]
+    fn counts_non_missing() {
+        let series: Series = Series::new(vec![1.0, 2.0, f64::NAN, 0.0, f64::NAN]);
+        for threshold in [usize::MAX, 1] {
+            assert_eq!(with_par_threshold(threshold, || series.count()), 3);
+        }
+        assert_eq!(series.size(), 5);
+    }
+
+    #[test]
+    fn matches_notna() {
+        let series: Series = Series::new((0..20_000).map(|i| if i % 3 == 0 { f64::NAN } else { i as f64 }).collect());
+        assert_eq!(series.count(), series.notna().sum().iloc(0) as usize);
+        assert_eq!(series.count(), 13_333);
+    }
+
+    #[test]
+    fn empty_and_all_nan() {
+        assert_eq!(Series::new(vec![]).count(), 0);
+        assert_eq!(Series::new(vec![f64::NAN; 4]).count(), 0);
+    }
+}
+
+#[cfg(test)]
+mod rle_tests {
+    use super::*;
+
+    const NAN: f64 = f64::NAN;
+
+    #[test]
+    fn round_trip_with_nan_run() {
+        let series: Series = Series::new(vec![1.0, 1.0, 1.0, NAN, NAN, 2.0, 1.0, NAN]);
+        let (values, lengths) = series.rle();
+        assert_eq!(lengths, vec![3, 2, 1, 1, 1]);
+        assert!(Series::new(values.clone()).approx_eq(&Series::new(vec![1.0, NAN, 2.0, 1.0, NAN]), 0.0));
+
+        let restored: Series = Series::from_rle(values, lengths);
+        assert!(restored.approx_eq(&series, 0.0));
+    }
+
+    #[test]
+    fn no_repeats() {
+        let series: Series = Series::new(vec![1.0, 2.0, 3.0]);
+        assert_eq!(series.rle(), (vec![1.0, 2.0, 3.0], vec![1, 1, 1]));
+    }
+
+    #[test]
+    fn empty() {
+        let (values, lengths) = Series::new(vec![]).rle();
+        assert!(values.is_empty() && lengths.is_empty());
+        assert!(Series::from_rle(values, lengths).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Need exactly one length per value")]
+    fn rejects_mismatched_lengths() {
+        Series::from_rle(vec![1.0, 2.0], vec![3]);
+    }
+}
+
+#[cfg(test)]
+mod isin_tests {
+    use super::*;
+
+    const NAN: f64 = f64::NAN;
+
+    #[test]
+    fn membership_mask() {
+        let series: Series = Series::new(vec![1.0, 2.0, NAN, 4.0, 2.0]);
+        assert_eq!(series.isin(vec![4.0, 2.0, 9.0]), Series::new(vec![0.0, 1.0, 0.0, 1.0, 1.0]));
+    }
+
+    #[test]
+    fn nan_only_matches_nan() {
+        let series: Series = Series::new(vec![1.0, 2.0, NAN]);
+        assert_eq!(series.isin(vec![1.0]), Series::new(vec![1.0, 0.0, 0.0]));
+        assert_eq!(series.isin(vec![NAN]), Series::new(vec![0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn empty_set_and_both_paths() {
+        let series: Series = Series::new((0..100).map(|i| i as f64).collect());
+        assert_eq!(series.isin(vec![]), Series::new(vec![0.0; 100]));
+        for threshold in [usize::MAX, 1] {
+            let mask: Series = with_par_threshold(threshold, || series.isin(vec![3.0, 50.0]));
+            assert_eq!(mask.sum(), Series::new(vec![2.0]));
+            assert_eq!(mask.iloc(50), 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod stable_sum_tests {
+    use super::*;
+
+    // 1e16 followed by many 1.0s, each of which a naive sum would round away
+    fn big_and_ones(ones: usize) -> Vec<f64> {
+        let mut data: Vec<f64> = vec![1e16];
+        data.extend(vec![1.0; ones]);
+        data.push(-1e16);
+        data
+    }
+
+    #[test]
+    fn recovers_the_ones() {
+        assert_eq!(big_and_ones(10).iter().sum::<f64>(), 0.0);
+        for threshold in [usize::MAX, 1024, 1] {
+            let sum: Series = with_par_threshold(threshold, || Series::new(big_and_ones(100_000)).sum());
+            assert_eq!(sum, Series::new(vec![100_000.0]), "threshold {}", threshold);
+        }
+    }
+
+    #[test]
+    fn mean_and_var_inherit_it() {
+        let series: Series = Series::new(big_and_ones(99_998));
+        assert_eq!(series.mean(), Series::new(vec![99_998.0 / 100_000.0]));
+
+        // Small deviations around a large offset
+        let series: Series = Series::new((0..10_000).map(|i| 1e9 + (i % 2) as f64).collect());
+        assert!((series.var().iloc(0) - 0.25 * 10_000.0 / 9_999.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn skips_nan() {
+        // Correctly rounded, where adding them up in order gives 0.6000000000000001
+        assert_eq!(Series::new(vec![0.1, f64::NAN, 0.2, 0.3]).sum(), Series::new(vec![0.6]));
+        assert_eq!(Series::new(vec![]).sum(), Series::new(vec![0.0]));
+    }
+}
+
+#[cfg(test)]
+mod constant_runs_tests {
+    use super::*;
+
+    fn runs(series: &Series, tolerance: f64) -> (Series, Series, Series) {
+        let runs: DataFrame = series.constant_runs(tolerance);
+        (runs.loc_col("start").unwrap(), runs.loc_col("length").unwrap(), runs.loc_col("value").unwrap())
+    }
+
+    #[test]
+    fn long_plateau() {
+        let mut data: Vec<f64> = vec![1.0, 2.0];
+        data.extend((0..50).map(|i| 5.0 + (i % 3) as f64 * 0.01));
+        data.extend([3.0, 4.0]);
+        let (starts, lengths, values) = runs(&Series::new(data), 0.05);
+        assert_eq!(starts, Series::new(vec![0.0, 1.0, 2.0, 52.0, 53.0]));
+        assert_eq!(lengths, Series::new(vec![1.0, 1.0, 50.0, 1.0, 1.0]));
+        assert_eq!(values, Series::new(vec![1.0, 2.0, 5.0, 3.0, 4.0]));
+    }
+
+    #[test]
+    fn ramp_is_not_a_plateau() {
+        // Each step is well within the tolerance, but the whole ramp spans 0.99
+        let series: Series = Series::new((0..100).map(|i| i as f64 * 0.01).collect());
+        let (starts, lengths, _) = runs(&series, 0.05);
+        assert!(starts.size() >= 100 / 6);
+        assert!(lengths.iter().all(|&n| n <= 6.0));
+        assert_eq!(lengths.sum(), Series::new(vec![100.0]));
+    }
+
+    #[test]
+    fn run_is_measured_against_all_its_values() {
+        // 1.04 is within 0.05 of 1.0 but 1.08 isn't, even though it's within 0.05 of 1.04
+        let (starts, lengths, values) = runs(&Series::new(vec![1.0, 1.04, 1.08, 1.06]), 0.05);
+        assert_eq!(starts, Series::new(vec![0.0, 2.0]));
+        assert_eq!(lengths, Series::new(vec![2.0, 2.0]));
+        assert_eq!(values, Series::new(vec![1.0, 1.08]));
+    }
+
+    #[test]
+    fn nan_breaks_runs() {
+        let (starts, lengths, _) = runs(&Series::new(vec![3.0, 3.0, f64::NAN, 3.0, f64::NAN]), 0.0);
+        assert_eq!(starts, Series::new(vec![0.0, 3.0]));
+        assert_eq!(lengths, Series::new(vec![2.0, 1.0]));
+    }
+}
+
+
+#[cfg(test)]
+mod rolling_rank_tests {
+    use super::*;
+
+    #[test]
+    fn window_max_ranks_one() {
+        let ranks: Vec<f64> = Series::new(vec![1.0, 3.0, 2.0, 5.0, 4.0]).rolling_rank(3).to_vec();
+        assert!(ranks[0].is_nan() && ranks[1].is_nan());
+        assert_eq!(ranks[2..], [2.0 / 3.0, 1.0, 2.0 / 3.0]);
+    }
+
+    #[test]
+    fn ties_share_their_average_rank() {
+        let ranks: Vec<f64> = Series::new(vec![2.0, 2.0, 2.0, 1.0]).rolling_rank(2).to_vec();
+        assert_eq!(ranks[1..], [0.75, 0.75, 0.5]);
+    }
+
+    #[test]
+    fn nans_are_ignored_in_the_window() {
+        let ranks: Vec<f64> = Series::new(vec![1.0, f64::NAN, 0.0, 4.0]).rolling_rank(3).to_vec();
+        assert!(ranks[1].is_nan());
+        // 0.0 is the smaller of the two values in its window, 4.0 the larger
+        assert_eq!(ranks[2..], [0.5, 1.0]);
+    }
+
+    #[test]
+    fn window_of_one_is_always_one() {
+        let ranks: Vec<f64> = Series::new(vec![3.0, 1.0, 2.0]).rolling_rank(1).to_vec();
+        assert_eq!(ranks, vec![1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn matches_counting_smaller_and_equal_values() {
+        let data: Vec<f64> = (0..50).map(|i| ((i * 37) % 11) as f64).collect();
+        let ranks: Vec<f64> = Series::new(data.clone()).rolling_rank(7).to_vec();
+        for i in 6..data.len() {
+            let window = &data[i - 6..=i];
+            let less = window.iter().filter(|&&y| y < data[i]).count() as f64;
+            let equal = window.iter().filter(|&&y| y == data[i]).count() as f64;
+            assert_eq!(ranks[i], (less + (equal + 1.0) / 2.0) / 7.0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Window size must be positive")]
+    fn zero_window_panics() {
+        Series::new(vec![1.0]).rolling_rank(0);
+    }
+}
+
+#[cfg(test)]
+mod str_tests {
+    use super::*;
+
+    #[test]
+    fn str_in_a_loop_gives_owned_equal_strings() {
+        let series: Series = Series::new(vec![1.5, f64::NAN, 3.0]);
+        let first: String = series.__str__();
+        assert_eq!(first, "[1.5, NaN, 3]");
+        for _ in 0..10_000 {
+            // An owned String that outlives the Series it came from
+            let s: String = series.clone().__str__();
+            assert_eq!(s, first);
+        }
+        assert_eq!(series.__repr__(), first);
+    }
+}
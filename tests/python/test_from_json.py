@@ -0,0 +1,23 @@
+# Run after building the extension (e.g. `maturin develop`): python tests/python/test_from_json.py
+import math
+from rusty_pandas import Series
+
+
+def test_numbers_and_nulls():
+    values = Series.from_json("[1.0, 2, null, 4.5]").to_vec()
+    assert values[:2] == [1.0, 2.0] and math.isnan(values[2]) and values[3] == 4.5
+
+
+def test_malformed_json_is_a_value_error():
+    for s in ["[1,2,]", '[1,"a"]', "[1, 2", "{}"]:
+        try:
+            Series.from_json(s)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"expected ValueError for {s}")
+
+
+if __name__ == "__main__":
+    test_numbers_and_nulls()
+    test_malformed_json_is_a_value_error()
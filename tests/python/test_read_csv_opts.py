@@ -0,0 +1,26 @@
+# Run from the repository root after building the extension (e.g. `maturin develop`):
+# python tests/python/test_read_csv_opts.py
+from rusty_pandas import read_csv, read_csv_opts
+
+
+def test_defaults_match_read_csv():
+    assert str(read_csv_opts("res/na_values.csv")) == str(read_csv("res/na_values.csv"))
+
+
+def test_options_by_keyword():
+    df = read_csv_opts("res/thousands.csv", thousands=",")
+    assert df.loc_col("price").to_vec() == [1234.5, 12000.0, 7.25]
+
+    df = read_csv_opts("res/no_header.tsv", delimiter="\t", has_header=False)
+    assert df.loc_col("1").to_vec() == [2.0, 5.0]
+
+    df = read_csv_opts("res/banner.csv", skip_rows=2, comment="#")
+    assert df.loc_col("count").to_vec() == [10.0, 30.0]
+
+    df = read_csv_opts("res/na_values.csv", na_values=["NA", "-999"])
+    assert df.loc_col("reading").fillna(0.0).to_vec() == [1.5, 0.0, 0.0, 2.0, 0.0]
+
+
+if __name__ == "__main__":
+    test_defaults_match_read_csv()
+    test_options_by_keyword()
@@ -0,0 +1,26 @@
+# Run after building the extension (e.g. `maturin develop`): python tests/python/test_rolling_apply.py
+import math
+from rusty_pandas import DataFrame, Series
+
+
+def test_spread_of_consecutive_values():
+    df = DataFrame([Series([1.0, 2.0, 4.0, 8.0]), Series([3.0, 3.0, 0.0, 1.0])], ["a", "b"])
+    spread = df.rolling_apply(2, lambda w: max(w) - min(w), 0)
+    assert math.isnan(spread.loc_col("a").to_vec()[0])
+    assert spread.loc_col("a").to_vec()[1:] == [1.0, 2.0, 4.0]
+    assert spread.loc_col("b").to_vec()[1:] == [0.0, 3.0, 1.0]
+
+
+def test_zero_window_is_a_value_error():
+    df = DataFrame([Series([1.0, 2.0])], ["a"])
+    try:
+        df.rolling_apply(0, sum, 0)
+    except ValueError as e:
+        assert "Window size must be positive" in str(e)
+    else:
+        raise AssertionError("expected ValueError")
+
+
+if __name__ == "__main__":
+    test_spread_of_consecutive_values()
+    test_zero_window_is_a_value_error()